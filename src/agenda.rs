@@ -0,0 +1,75 @@
+use ratatui::widgets::ListState;
+use time::OffsetDateTime;
+
+use crate::task::{Task, TaskStatus};
+
+/// The headings unfinished tasks are grouped under, in the order they are listed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaGroup {
+    Overdue,
+    Today,
+    Tomorrow,
+    /// Due within the next seven days.
+    ThisWeek,
+    Later,
+}
+
+impl AgendaGroup {
+    pub(crate) const fn title(self) -> &'static str {
+        match self {
+            AgendaGroup::Overdue => "Overdue",
+            AgendaGroup::Today => "Today",
+            AgendaGroup::Tomorrow => "Tomorrow",
+            AgendaGroup::ThisWeek => "This Week",
+            AgendaGroup::Later => "Later",
+        }
+    }
+
+    /// Where `task` is listed, finished and deferred tasks are left out.
+    fn of(task: &Task) -> Option<AgendaGroup> {
+        if !matches!(task.task_status, TaskStatus::InProgress) || task.is_deferred() {
+            return None;
+        }
+        if task.is_overdue() {
+            return Some(AgendaGroup::Overdue);
+        }
+        // count whole days in the time zone of the due time
+        let today = OffsetDateTime::now_utc()
+            .to_offset(task.due_time.offset())
+            .date();
+        let group = match (task.due_time.date() - today).whole_days() {
+            days if days <= 0 => AgendaGroup::Today,
+            1 => AgendaGroup::Tomorrow,
+            2..=6 => AgendaGroup::ThisWeek,
+            _ => AgendaGroup::Later,
+        };
+        Some(group)
+    }
+}
+
+/// The unfinished tasks in agenda order with their group, as indices into `tasks`.
+pub(crate) fn agenda_rows<'a>(tasks: impl Iterator<Item = &'a Task>) -> Vec<(AgendaGroup, usize)> {
+    let tasks: Vec<&Task> = tasks.collect();
+    let mut rows: Vec<(AgendaGroup, usize)> = tasks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, task)| AgendaGroup::of(task).map(|group| (group, i)))
+        .collect();
+    rows.sort_by_key(|(group, i)| (*group, tasks[*i].due_time));
+    rows
+}
+
+/// The highlighted task of the agenda screen.
+#[derive(Default)]
+pub struct AgendaState {
+    /// Index into the agenda rows, clamped when rendered.
+    pub selected: usize,
+    /// Scroll position of the list, which also has a line per heading.
+    pub list: ListState,
+}
+
+impl AgendaState {
+    pub(crate) fn move_selection(&mut self, rows: isize) {
+        self.selected = self.selected.saturating_add_signed(rows);
+    }
+}
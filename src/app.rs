@@ -1,13 +1,46 @@
+use std::mem;
+
+use crossterm::event::KeyEvent;
 use rand::seq::SliceRandom;
+use ratatui::widgets::ListState;
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use time::{Date, Duration, OffsetDateTime};
 
-use crate::task::{Task, TaskStatus};
+use crate::{
+    agenda::{agenda_rows, AgendaState},
+    board::{column_rows, column_status, BoardState},
+    calendar::CalendarState,
+    command_line::CommandLineState,
+    command_palette::CommandPaletteState,
+    config::Config,
+    input_keys::{ActionKind, KeyBindings, KeyContext, KeyMatch, KeyPress},
+    keys_hint::KeysHintState,
+    panes::PaneLayout,
+    planner::{day_rows, PlannerState},
+    pomodoro::{FocusTimer, Period},
+    reminders::{self, Notice, Reminders},
+    task::{SortKey, Task, TaskStatus, TaskViewState},
+    text_area::TextArea,
+    theme::Theme,
+    tracking::TimeReport,
+    ui::ClickAreas,
+    vim::Vim,
+};
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum CurrentScreen {
     Main,
     Editing,
+    /// A month of due dates with the tasks of the selected day.
+    Calendar,
+    /// Unfinished tasks grouped by when they are due.
+    Agenda,
+    /// A column of tasks for every day of a week.
+    Planner,
+    /// A column of task cards for every status.
+    Board,
+    /// Charts of finished and open tasks over time.
+    Dashboard,
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, PartialOrd, Ord, Eq)]
@@ -23,38 +56,167 @@ pub enum TaskCreationMode {
     CreateNew,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy)]
 pub enum Popup {
     Help,
     Error,
+    CommandPalette,
+    /// The `:` command line in place of the hint bar.
+    CommandLine,
+    /// The result of `:report`.
+    TimeReport,
+    /// Reminders that went off.
+    Reminder,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct App {
-    pub title_input: String,
-    pub description_input: String,
+    pub title_input: TextArea,
+    pub description_input: TextArea,
     pub current_screen: CurrentScreen, // the current screen the user is looking at, and will later determine what is rendered.
     pub current_task: Option<Task>,
     pub edit_mode: Option<EditMode>,
+    /// Popups show state that is not saved, so they are not saved either.
+    #[serde(skip)]
     pub popup: Option<Popup>,
+    #[serde(skip)]
+    pub error_message: Option<String>,
     pub task_creation_mode: TaskCreationMode,
+    /// Due date picked on the editor's calendar for the task being created.
+    #[serde(skip)]
+    pub new_task_due: Option<Date>,
     pub tasks: Vec<Task>,
+    #[serde(skip)]
+    pub task_view: TaskViewState,
+    #[serde(skip)]
+    pub task_list: ListState,
+    #[serde(skip)]
+    pub click_areas: ClickAreas,
+    #[serde(skip)]
+    pub keys_hint: KeysHintState,
+    #[serde(skip)]
+    pub command_palette: CommandPaletteState,
+    #[serde(skip)]
+    pub command_line: CommandLineState,
+    /// Commands run from the command line, oldest first.
+    #[serde(default)]
+    pub command_history: Vec<String>,
+    #[serde(skip)]
+    pub config: Config,
+    #[serde(skip)]
+    pub key_bindings: KeyBindings,
+    #[serde(skip)]
+    pub theme: Theme,
+    #[serde(skip)]
+    pub panes: PaneLayout,
+    #[serde(skip)]
+    pub calendar: CalendarState,
+    #[serde(skip)]
+    pub agenda: AgendaState,
+    #[serde(skip)]
+    pub planner: PlannerState,
+    #[serde(skip)]
+    pub board: BoardState,
+    #[serde(skip)]
+    pub focus_timer: Option<FocusTimer>,
+    /// Shown in the time report popup.
+    #[serde(skip)]
+    pub time_report: Option<TimeReport>,
+    #[serde(skip)]
+    pub reminders: Reminders,
+    /// Reminders that went off and were not yet dismissed.
+    #[serde(skip)]
+    pub notices: Vec<Notice>,
+    /// Keys of a chord typed so far, e.g. the first `g` of `g g`.
+    #[serde(skip)]
+    pub pending_keys: Vec<KeyPress>,
+    #[serde(skip)]
+    pub vim: Vim,
 }
 
 impl App {
     pub(crate) fn new() -> App {
         App {
-            title_input: String::new(),
-            description_input: String::new(),
+            title_input: TextArea::default(),
+            description_input: TextArea::default(),
             current_screen: CurrentScreen::Main,
             current_task: None,
             edit_mode: None,
             popup: None,
+            error_message: None,
             task_creation_mode: TaskCreationMode::CreateNew,
+            new_task_due: None,
             tasks: Vec::new(),
+            task_view: TaskViewState::default(),
+            task_list: ListState::default(),
+            click_areas: ClickAreas::default(),
+            keys_hint: KeysHintState::default(),
+            command_palette: CommandPaletteState::default(),
+            command_line: CommandLineState::default(),
+            command_history: Vec::new(),
+            config: Config::default(),
+            key_bindings: KeyBindings::default(),
+            theme: Theme::default(),
+            panes: PaneLayout::default(),
+            calendar: CalendarState::default(),
+            agenda: AgendaState::default(),
+            planner: PlannerState::default(),
+            board: BoardState::default(),
+            focus_timer: None,
+            time_report: None,
+            reminders: Reminders::default(),
+            notices: Vec::new(),
+            pending_keys: Vec::new(),
+            vim: Vim::default(),
+        }
+    }
+
+    /// Which key bindings apply to what the user is looking at.
+    pub(crate) fn key_context(&self) -> KeyContext {
+        match (self.current_screen, self.edit_mode) {
+            (CurrentScreen::Main, _) => KeyContext::Main,
+            (CurrentScreen::Editing, Some(EditMode::Title | EditMode::Description)) => {
+                KeyContext::TextField
+            }
+            (CurrentScreen::Editing, _) => KeyContext::Editing,
+            (CurrentScreen::Calendar, _) => KeyContext::Calendar,
+            (CurrentScreen::Agenda, _) => KeyContext::Agenda,
+            (CurrentScreen::Planner, _) => KeyContext::Planner,
+            (CurrentScreen::Board, _) => KeyContext::Board,
+            (CurrentScreen::Dashboard, _) => KeyContext::Dashboard,
+        }
+    }
+
+    /// Feeds a key into the bindings, returning the action once a chord is complete.
+    pub(crate) fn action_for_key(&mut self, key: KeyEvent) -> Option<ActionKind> {
+        self.pending_keys.push(KeyPress::from(key));
+        match self
+            .key_bindings
+            .resolve(self.key_context(), &self.pending_keys)
+        {
+            KeyMatch::Action(action) => {
+                self.pending_keys.clear();
+                Some(action)
+            }
+            KeyMatch::Pending => None,
+            KeyMatch::None => {
+                // an unfinished chord is dropped, but the key may start a new one
+                let retry = self.pending_keys.len() > 1;
+                self.pending_keys.clear();
+                if retry {
+                    self.action_for_key(key)
+                } else {
+                    None
+                }
+            }
         }
     }
 
+    pub(crate) fn show_error(&mut self, message: String) {
+        self.error_message = Some(message);
+        self.popup = Some(Popup::Error);
+    }
+
     pub(crate) fn save_task(&mut self) {
         if self.title_input.is_empty() && self.description_input.is_empty() {
             return;
@@ -65,27 +227,110 @@ impl App {
                     .current_task
                     .as_mut()
                     .expect("editing an active task that exists");
-                t.title = self.title_input.clone();
+                t.title = self.title_input.text();
                 let description = if self.description_input.is_empty() {
                     None
                 } else {
-                    Some(self.description_input.clone())
+                    Some(self.description_input.text())
                 };
                 t.description = description;
                 t.time_edited = OffsetDateTime::now_local().unwrap();
             }
             TaskCreationMode::CreateNew => {
-                let new_task = if self.description_input.is_empty() {
-                    Task::default(self.title_input.clone())
+                let mut new_task = if self.description_input.is_empty() {
+                    Task::default(self.title_input.text())
                 } else {
-                    Task::new(self.title_input.clone(), self.description_input.clone())
+                    Task::new(self.title_input.text(), self.description_input.text())
                 };
+                if let Some(date) = self.new_task_due.take() {
+                    new_task.set_due_date(date);
+                }
                 self.tasks.push(new_task);
             }
         }
         self.choose_shown_task();
     }
 
+    /// Opens the editing screen, for the current task or for a new one.
+    pub(crate) fn start_editing(&mut self, creation_mode: TaskCreationMode) {
+        let task = match creation_mode {
+            TaskCreationMode::Active => self.current_task.as_ref(),
+            TaskCreationMode::CreateNew => None,
+        };
+        self.task_creation_mode = match task {
+            Some(_) => TaskCreationMode::Active,
+            None => TaskCreationMode::CreateNew,
+        };
+        match task {
+            Some(task) => {
+                self.title_input.set_text(&task.title);
+                self.description_input
+                    .set_text(task.description.as_deref().unwrap_or_default());
+            }
+            None => {
+                self.title_input.clear();
+                self.description_input.clear();
+            }
+        }
+        self.new_task_due = None;
+        self.current_screen = CurrentScreen::Editing;
+        self.edit_mode = Some(EditMode::Main);
+    }
+
+    /// Sets the due date of the task in the editor, a new one gets it once saved.
+    pub(crate) fn set_edited_task_due_date(&mut self, date: Date) {
+        match self.task_creation_mode {
+            TaskCreationMode::Active => self.set_active_task_due_date(date),
+            TaskCreationMode::CreateNew => self.new_task_due = Some(date),
+        }
+    }
+
+    pub(crate) fn set_active_task_due_date(&mut self, date: Date) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.set_due_date(date);
+        }
+    }
+
+    pub(crate) fn set_active_task_estimate(&mut self, estimate: Option<Duration>) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.estimate = estimate;
+        }
+    }
+
+    pub(crate) fn add_active_task_reminder(&mut self, before: Duration) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.add_reminder(before);
+        }
+    }
+
+    pub(crate) fn clear_active_task_reminders(&mut self) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.reminders.clear();
+        }
+    }
+
+    pub(crate) fn set_active_task_start(&mut self, start: Option<OffsetDateTime>) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.start_time = start;
+        }
+        self.replace_deferred_task();
+    }
+
+    /// Defers the shown task by `duration`, another task takes its place.
+    pub(crate) fn snooze_active_task(&mut self, duration: Duration) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.snooze(duration);
+        }
+        self.replace_deferred_task();
+    }
+
+    /// Picks another task once the shown one is deferred, on every screen.
+    fn replace_deferred_task(&mut self) {
+        if self.current_task.as_ref().is_some_and(Task::is_deferred) {
+            self.choose_shown_task();
+        }
+    }
+
     pub(crate) fn change_active_task_due_date(&mut self, num_days: i64) {
         if let Some(ref mut active_task) = &mut self.current_task {
             active_task.change_due_date(num_days);
@@ -94,15 +339,360 @@ impl App {
 
     pub fn change_task_status(&mut self, new_status: TaskStatus) {
         if let Some(ref mut active_task) = &mut self.current_task {
-            active_task.task_status = new_status;
+            active_task.set_status(new_status);
+        }
+    }
+
+    pub fn scroll_description(&mut self, lines: i16) {
+        let scroll = &mut self.task_view.description_scroll;
+        *scroll = scroll.saturating_add_signed(lines);
+    }
+
+    /// Adds a task without editing it first, showing it if nothing is shown.
+    pub(crate) fn add_task(&mut self, task: Task) {
+        self.tasks.push(task);
+        if self.current_task.is_none() {
+            self.choose_shown_task();
+        }
+    }
+
+    pub(crate) fn tag_active_task(&mut self, tag: &str) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.add_tag(tag);
         }
     }
 
+    pub(crate) fn untag_active_task(&mut self, tag: &str) {
+        if let Some(active_task) = &mut self.current_task {
+            active_task.remove_tag(tag);
+        }
+    }
+
+    /// Every tag in use, sorted and without duplicates.
+    pub(crate) fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .all_tasks()
+            .flat_map(|task| task.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// The shown task followed by the rest.
+    pub(crate) fn all_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.current_task.iter().chain(self.tasks.iter())
+    }
+
+    /// Orders the tasks by `key` and shows the first one.
+    pub(crate) fn sort_tasks(&mut self, key: SortKey) {
+        if let Some(task) = self.current_task.take() {
+            self.tasks.push(task);
+        }
+        self.tasks.sort_by(|a, b| key.compare(a, b));
+        if !self.tasks.is_empty() {
+            self.current_task = Some(self.tasks.remove(0));
+        }
+        self.task_view.description_scroll = 0;
+    }
+
+    /// Shows `tasks[index]`, putting the current task in its place.
+    pub(crate) fn select_task(&mut self, index: usize) {
+        if index >= self.tasks.len() {
+            return;
+        }
+        let task = match self.current_task.take() {
+            Some(current) => mem::replace(&mut self.tasks[index], current),
+            None => self.tasks.remove(index),
+        };
+        self.current_task = Some(task);
+        self.task_view.description_scroll = 0;
+    }
+
+    /// The tasks of the task list as indices into `all_tasks`, deferred ones
+    /// are left out unless shown.
+    pub(crate) fn listed_tasks(&self) -> Vec<usize> {
+        let shown = usize::from(self.current_task.is_some());
+        let waiting = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| !task.is_deferred())
+            .map(|(i, _)| i + shown);
+        (0..shown).chain(waiting).collect()
+    }
+
+    /// Shows the task at `index` of `all_tasks`.
+    pub(crate) fn show_task(&mut self, index: usize) {
+        // the first one is the shown task itself
+        match (self.current_task.is_some(), index) {
+            (true, 0) => {}
+            (true, index) => self.select_task(index - 1),
+            (false, index) => self.select_task(index),
+        }
+    }
+
+    /// Shows the task selected on the current screen, so the task actions
+    /// apply to it. `false` when the screen has nothing selected.
+    pub(crate) fn show_selected_task(&mut self) -> bool {
+        let selected = match self.current_screen {
+            CurrentScreen::Agenda => {
+                let rows = agenda_rows(self.all_tasks());
+                let selected = self.agenda.selected.min(rows.len().saturating_sub(1));
+                rows.get(selected).map(|(_, index)| *index)
+            }
+            CurrentScreen::Planner => {
+                let rows = day_rows(self.all_tasks(), self.planner.day());
+                let selected = self.planner.row.min(rows.len().saturating_sub(1));
+                rows.get(selected).copied()
+            }
+            CurrentScreen::Board => {
+                let rows = column_rows(self.all_tasks(), self.board.status());
+                let selected = self.board.row.min(rows.len().saturating_sub(1));
+                rows.get(selected).copied()
+            }
+            CurrentScreen::Main
+            | CurrentScreen::Editing
+            | CurrentScreen::Calendar
+            | CurrentScreen::Dashboard => return true,
+        };
+        match selected {
+            Some(index) => {
+                self.show_task(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reschedules the task selected in the planner by `days`, the selection
+    /// follows it to its new day.
+    pub(crate) fn move_planned_task(&mut self, days: i64) {
+        if !self.show_selected_task() {
+            return;
+        }
+        self.change_active_task_due_date(days);
+        let day = self.planner.day().saturating_add(Duration::days(days));
+        let row = shown_task_row(&day_rows(self.all_tasks(), day)).unwrap_or_default();
+        self.planner.select(day, row);
+    }
+
+    /// Moves the card selected on the board to another column, changing its
+    /// status, the selection follows it.
+    pub(crate) fn move_board_card(&mut self, columns: isize) {
+        if !self.show_selected_task() {
+            return;
+        }
+        self.board.move_column(columns);
+        let status = column_status(self.board.column);
+        self.change_task_status(status);
+        self.board.row = shown_task_row(&column_rows(self.all_tasks(), status)).unwrap_or_default();
+    }
+
+    /// Moves the task selected in the planner up or down within its day by
+    /// trading due times with its neighbour.
+    pub(crate) fn reorder_planned_task(&mut self, rows: isize) {
+        if !self.show_selected_task() {
+            return;
+        }
+        let day_rows = day_rows(self.all_tasks(), self.planner.day());
+        let Some(row) = shown_task_row(&day_rows) else {
+            return;
+        };
+        let Some(neighbour) = row
+            .checked_add_signed(rows)
+            .and_then(|row| day_rows.get(row))
+        else {
+            return;
+        };
+        let (Some(task), Some(other)) = (&mut self.current_task, self.tasks.get_mut(neighbour - 1))
+        else {
+            return;
+        };
+        if task.due_time == other.due_time {
+            // swapping equal times would keep the order, step past the neighbour
+            // instead, moving the neighbour when the task would leave its day
+            let step = Duration::minutes(rows as i64);
+            if (task.due_time + step).date() == task.due_time.date() {
+                task.due_time += step;
+            } else {
+                other.due_time -= step;
+            }
+        } else {
+            mem::swap(&mut task.due_time, &mut other.due_time);
+        }
+        self.planner.row = row.saturating_add_signed(rows);
+    }
+
+    /// Scrolls the task list, clamped when rendered.
+    pub(crate) fn scroll_task_list(&mut self, lines: isize) {
+        let offset = self.task_list.offset_mut();
+        *offset = offset.saturating_add_signed(lines);
+    }
+
+    /// Starts a work period on the shown task, or stops the timer keeping
+    /// the time worked so far.
+    pub(crate) fn toggle_focus_timer(&mut self) {
+        match self.focus_timer.take() {
+            Some(timer) => self.record_focus_session(&timer),
+            None => {
+                if let Some(task) = &self.current_task {
+                    let timer = FocusTimer::start(Period::Work, task.id, &self.config.pomodoro);
+                    self.focus_timer = Some(timer);
+                }
+            }
+        }
+    }
+
+    /// Starts tracking time on the shown task, stopping the timer of any
+    /// other task, or stops it when it is already running.
+    pub(crate) fn toggle_time_tracking(&mut self) {
+        let Some(task) = &mut self.current_task else {
+            return;
+        };
+        if task.is_tracking() {
+            task.stop_tracking();
+            return;
+        }
+        for other in &mut self.tasks {
+            other.stop_tracking();
+        }
+        task.start_tracking();
+    }
+
+    /// Shows the reminders that went off and passes them to the notifier,
+    /// `true` when there were any.
+    pub(crate) fn check_reminders(&mut self) -> bool {
+        let now = self.reminders.clock.now();
+        let tasks = self.current_task.iter_mut().chain(self.tasks.iter_mut());
+        let notices = reminders::take_due(tasks, now);
+        let mut failed = None;
+        if let Some(notifier) = &mut self.reminders.notifier {
+            failed = notifier.finished().err();
+            for notice in &notices {
+                if let Err(e) = notifier.notify(notice) {
+                    failed = Some(e);
+                }
+            }
+        }
+        let fired = !notices.is_empty();
+        self.notices.extend(notices);
+        if let Some(e) = failed {
+            self.show_error(format!("Could not run the reminder command: {}", e));
+        }
+        // wait for other popups to close and the field to lose focus, so
+        // typing is not interrupted
+        if self.popup.is_none()
+            && self.key_context() != KeyContext::TextField
+            && !self.notices.is_empty()
+        {
+            self.popup = Some(Popup::Reminder);
+        }
+        fired
+    }
+
+    /// Moves the focus timer on to the next period once the current one is
+    /// over, `true` when that happened.
+    pub(crate) fn tick(&mut self) -> bool {
+        let Some(timer) = self.focus_timer.take() else {
+            return false;
+        };
+        if !timer.is_over() {
+            self.focus_timer = Some(timer);
+            return false;
+        }
+        self.record_focus_session(&timer);
+        let next = match timer.period {
+            Period::Work => Period::Break,
+            Period::Break => Period::Work,
+        };
+        self.focus_timer = Some(FocusTimer::start(next, timer.task(), &self.config.pomodoro));
+        true
+    }
+
+    /// Adds the time worked to the task the timer was started on, even if
+    /// another one is shown by now.
+    fn record_focus_session(&mut self, timer: &FocusTimer) {
+        let Some(session) = timer.session() else {
+            return;
+        };
+        let task = self
+            .current_task
+            .iter_mut()
+            .chain(self.tasks.iter_mut())
+            .find(|task| task.id == timer.task());
+        if let Some(task) = task {
+            task.focus_sessions.push(session);
+        }
+    }
+
+    /// How many tasks are overdue and how many are due later today.
+    pub(crate) fn due_counts(&self) -> (usize, usize) {
+        let overdue = self.all_tasks().filter(|task| task.is_overdue()).count();
+        let due_today = self.all_tasks().filter(|task| task.is_due_today()).count();
+        (overdue, due_today)
+    }
+
     pub fn choose_shown_task(&mut self) {
         if let Some(task) = &self.current_task {
             self.tasks.push(task.clone());
         }
         self.tasks.shuffle(&mut rand::thread_rng());
-        self.current_task = self.tasks.pop();
+        // the sorts are stable, so the tasks picked from stay shuffled at the end
+        if self.config.shuffle.favour_overdue {
+            self.tasks.sort_by_key(Task::is_overdue);
+        }
+        // deferred tasks are never picked, nothing is shown when only they are left
+        self.tasks.sort_by_key(|task| !task.is_deferred());
+        self.current_task = match self.tasks.last() {
+            Some(task) if !task.is_deferred() => self.tasks.pop(),
+            _ => None,
+        };
+        self.task_view.description_scroll = 0;
+    }
+
+    /// Picks a task on the main screen when none is shown and one of the
+    /// deferred tasks has started.
+    pub(crate) fn show_started_task(&mut self) {
+        if matches!(self.current_screen, CurrentScreen::Main)
+            && self.current_task.is_none()
+            && self.tasks.iter().any(|task| !task.is_deferred())
+        {
+            self.choose_shown_task();
+        }
+    }
+}
+
+/// The row of the shown task among `rows` of indices into `all_tasks`. The
+/// task actions apply to the shown task, so a task that was just moved is
+/// the first of `all_tasks`.
+fn shown_task_row(rows: &[usize]) -> Option<usize> {
+    rows.iter().position(|index| *index == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deferred_tasks_are_replaced_on_every_screen() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Agenda;
+        app.current_task = Some(Task::default("first".to_string()));
+        app.tasks = vec![Task::default("second".to_string())];
+
+        app.snooze_active_task(Duration::HOUR);
+        let shown = app.current_task.as_ref().map(|task| task.title.as_str());
+        assert_eq!(shown, Some("second"));
+
+        let tomorrow = OffsetDateTime::now_utc() + Duration::DAY;
+        app.set_active_task_start(Some(tomorrow));
+        assert!(app.current_task.is_none());
+        assert_eq!(app.tasks.len(), 2);
+
+        // a start date in the past does not defer the task
+        app.current_task = app.tasks.pop();
+        app.set_active_task_start(Some(OffsetDateTime::now_utc() - Duration::DAY));
+        assert!(app.current_task.is_some());
     }
 }
@@ -0,0 +1,53 @@
+use ratatui::widgets::ListState;
+use strum::IntoEnumIterator;
+
+use crate::task::{Task, TaskStatus};
+
+/// The selected card of the board screen.
+#[derive(Default)]
+pub struct BoardState {
+    /// Index into the statuses, one column each.
+    pub column: usize,
+    /// Index into the cards of the column, clamped when used.
+    pub row: usize,
+    /// Scroll position of the selected column.
+    pub list: ListState,
+}
+
+impl BoardState {
+    /// The status of the selected column.
+    pub(crate) fn status(&self) -> TaskStatus {
+        column_status(self.column)
+    }
+
+    pub(crate) fn move_column(&mut self, columns: isize) {
+        let last = TaskStatus::iter().count() - 1;
+        self.column = self.column.saturating_add_signed(columns).min(last);
+        self.row = 0;
+    }
+
+    pub(crate) fn move_row(&mut self, rows: isize) {
+        self.row = self.row.saturating_add_signed(rows);
+    }
+}
+
+/// The status shown in `column`, the last one past the end.
+pub(crate) fn column_status(column: usize) -> TaskStatus {
+    TaskStatus::iter()
+        .nth(column)
+        .or_else(|| TaskStatus::iter().next_back())
+        .expect("there is a status")
+}
+
+/// The tasks with `status` by due time, as indices into `tasks`.
+pub(crate) fn column_rows<'a>(
+    tasks: impl Iterator<Item = &'a Task>,
+    status: TaskStatus,
+) -> Vec<usize> {
+    let tasks: Vec<&Task> = tasks.collect();
+    let mut rows: Vec<usize> = (0..tasks.len())
+        .filter(|i| tasks[*i].task_status == status)
+        .collect();
+    rows.sort_by_key(|i| tasks[*i].due_time);
+    rows
+}
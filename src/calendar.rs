@@ -0,0 +1,41 @@
+use time::{util::days_in_year_month, Date, Duration, Month};
+
+use crate::dates;
+
+/// The day picked on the calendar screen.
+#[derive(Default)]
+pub struct CalendarState {
+    /// Today until another day is picked.
+    selected: Option<Date>,
+}
+
+impl CalendarState {
+    pub(crate) fn selected(&self) -> Date {
+        self.selected.unwrap_or_else(dates::today)
+    }
+
+    pub(crate) fn select(&mut self, date: Date) {
+        self.selected = Some(date);
+    }
+
+    pub(crate) fn select_today(&mut self) {
+        self.selected = None;
+    }
+
+    pub(crate) fn move_days(&mut self, days: i64) {
+        let date = self.selected().saturating_add(Duration::days(days));
+        self.select(date);
+    }
+
+    /// Moves by whole months, keeping the day where the month is long enough.
+    pub(crate) fn move_months(&mut self, months: i32) {
+        let date = self.selected();
+        let index = date.year() * 12 + i32::from(u8::from(date.month())) - 1 + months;
+        let year = index.div_euclid(12);
+        let month = Month::January.nth_next(index.rem_euclid(12) as u8);
+        let day = date.day().min(days_in_year_month(year, month));
+        if let Ok(date) = Date::from_calendar_date(year, month, day) {
+            self.select(date);
+        }
+    }
+}
@@ -0,0 +1,516 @@
+use std::{path::PathBuf, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use time::{Date, Duration, Month};
+
+use crate::{
+    task::{SortKey, SORT_KEYS},
+    text_area::TextArea,
+};
+
+/// The commands of the command line with a hint of their arguments.
+const COMMANDS: [(&str, &str); 14] = [
+    ("add", "<title>"),
+    ("due", "+<n>d | -<n>d | +<n>w"),
+    ("tag", "<tag> | -<tag>"),
+    ("done", ""),
+    ("sort", "due | added | edited | title"),
+    ("w", ""),
+    ("q", ""),
+    ("wq", ""),
+    ("export", "csv <path>"),
+    ("estimate", "<duration, e.g. 1h30m> | none"),
+    ("report", "[from YYYY-MM-DD] [to YYYY-MM-DD]"),
+    ("snooze", "<duration, e.g. 3h or 2d>"),
+    ("start", "<YYYY-MM-DD> | none"),
+    ("remind", "<time before due, e.g. 1h> | none"),
+];
+
+/// Commands are kept in the history up to this many.
+const MAX_HISTORY: usize = 100;
+
+/// A command typed into the `:` command line.
+pub(crate) enum Command {
+    /// Adds a task with the given title.
+    Add(String),
+    /// Moves the due date of the shown task by a number of days.
+    Due(i64),
+    Tag(String),
+    Untag(String),
+    Done,
+    Sort(SortKey),
+    Write,
+    Quit,
+    WriteQuit,
+    ExportCsv(PathBuf),
+    /// Sets or clears the time estimate of the shown task.
+    Estimate(Option<Duration>),
+    /// Shows the time tracked between two days, the last week by default.
+    Report(Option<Date>, Option<Date>),
+    /// Defers the shown task for a while.
+    Snooze(Duration),
+    /// Sets or clears the day the shown task starts.
+    Start(Option<Date>),
+    /// Adds a reminder the given time before the due time, `None` removes them all.
+    Remind(Option<Duration>),
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim().trim_start_matches(':');
+        let (name, args) = match line.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (line, ""),
+        };
+        let no_args = |command: Command| {
+            if args.is_empty() {
+                Ok(command)
+            } else {
+                Err(format!(":{} takes no arguments", name))
+            }
+        };
+        match name {
+            "add" | "a" if !args.is_empty() => Ok(Command::Add(args.to_string())),
+            "due" => parse_days(args).map(Command::Due),
+            "tag" if !args.is_empty() && !args.contains(char::is_whitespace) => {
+                match args.strip_prefix('-') {
+                    Some(tag) if !tag.is_empty() => Ok(Command::Untag(tag.to_string())),
+                    Some(_) => Err(usage("tag")),
+                    None => Ok(Command::Tag(args.to_string())),
+                }
+            }
+            "done" => no_args(Command::Done),
+            "sort" => SortKey::from_str(args).map(Command::Sort),
+            "w" | "write" => no_args(Command::Write),
+            "q" | "quit" => no_args(Command::Quit),
+            "wq" | "x" => no_args(Command::WriteQuit),
+            "export" => match args.split_once(char::is_whitespace) {
+                Some(("csv", path)) => Ok(Command::ExportCsv(PathBuf::from(path.trim()))),
+                Some((format, _)) => Err(format!(
+                    "can not export as `{}`, only csv is supported",
+                    format
+                )),
+                None => Err(usage("export")),
+            },
+            "estimate" => match args {
+                "none" => Ok(Command::Estimate(None)),
+                _ => parse_duration(args, "estimate")
+                    .map(|estimate| Command::Estimate(Some(estimate))),
+            },
+            "snooze" => parse_duration(args, "snooze").map(Command::Snooze),
+            "remind" => match args {
+                "none" => Ok(Command::Remind(None)),
+                _ => parse_duration(args, "remind").map(|before| Command::Remind(Some(before))),
+            },
+            "start" => match args {
+                "none" => Ok(Command::Start(None)),
+                "" => Err(usage("start")),
+                _ => parse_date(args).map(|date| Command::Start(Some(date))),
+            },
+            "report" => {
+                let dates: Vec<&str> = args.split_whitespace().collect();
+                match dates.as_slice() {
+                    [] => Ok(Command::Report(None, None)),
+                    [from] => Ok(Command::Report(Some(parse_date(from)?), None)),
+                    [from, to] => {
+                        let (from, to) = (parse_date(from)?, parse_date(to)?);
+                        if from > to {
+                            return Err(format!("{} is after {}", from, to));
+                        }
+                        Ok(Command::Report(Some(from), Some(to)))
+                    }
+                    _ => Err(usage("report")),
+                }
+            }
+            "" => Err("no command given".to_string()),
+            "add" | "a" => Err(usage("add")),
+            "tag" => Err(usage("tag")),
+            _ => {
+                let names: Vec<&str> = COMMANDS.iter().map(|(name, _)| *name).collect();
+                Err(format!(
+                    "unknown command `{}`, try one of {}",
+                    name,
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+fn usage(name: &str) -> String {
+    let args = COMMANDS
+        .iter()
+        .find(|(command, _)| *command == name)
+        .map_or("", |(_, args)| args);
+    format!("usage: :{} {}", name, args)
+}
+
+/// Parses `+3d`, `-1d`, `+2w` or plain `3` into a number of days.
+fn parse_days(arg: &str) -> Result<i64, String> {
+    let (number, unit) = match arg.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (arg.strip_suffix('d').unwrap_or(arg), 1),
+    };
+    let number = number.strip_prefix('+').unwrap_or(number);
+    match number.parse::<i64>() {
+        Ok(n) => n.checked_mul(unit).ok_or_else(|| usage("due")),
+        Err(_) => Err(usage("due")),
+    }
+}
+
+/// Parses a duration like `90m`, `2h`, `1h30m` or `2d` for the command `name`.
+fn parse_duration(arg: &str, name: &str) -> Result<Duration, String> {
+    let mut total = Duration::ZERO;
+    let mut number = String::new();
+    for ch in arg.chars() {
+        match ch {
+            '0'..='9' => number.push(ch),
+            'd' | 'h' | 'm' => {
+                // large enough for any duration while keeping clear of overflow
+                let n = number
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|n| *n <= 100_000)
+                    .ok_or_else(|| usage(name))?;
+                total += match ch {
+                    'd' => Duration::days(n),
+                    'h' => Duration::hours(n),
+                    _ => Duration::minutes(n),
+                };
+                number.clear();
+            }
+            _ => return Err(usage(name)),
+        }
+    }
+    if number.is_empty() && total.is_positive() {
+        Ok(total)
+    } else {
+        Err(usage(name))
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date.
+fn parse_date(arg: &str) -> Result<Date, String> {
+    let invalid = || format!("`{}` is not a date like 2024-01-31", arg);
+    let parts: Vec<&str> = arg.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let year = year.parse::<i32>().map_err(|_| invalid())?;
+    let month = match month.parse::<u8>() {
+        Ok(month @ 1..=12) => Month::January.nth_next(month - 1),
+        _ => return Err(invalid()),
+    };
+    let day = day.parse::<u8>().map_err(|_| invalid())?;
+    Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+}
+
+/// What a key did to the command line.
+pub(crate) enum CommandLineOutcome {
+    Handled,
+    /// The key is not used by the command line.
+    Ignored,
+    Cancel,
+    /// Enter was pressed on the given command.
+    Run(String),
+}
+
+/// The text typed after `:`, with the history and tab completion.
+#[derive(Default)]
+pub struct CommandLineState {
+    pub input: TextArea,
+    /// Position in the history while browsing it with Up and Down.
+    history_index: Option<usize>,
+    /// What was typed before browsing the history, restored past its end.
+    draft: String,
+    completion: Option<Completion>,
+}
+
+/// Candidates for the word being completed, cycled with Tab.
+struct Completion {
+    candidates: Vec<String>,
+    selected: usize,
+    /// The text before the completed word.
+    prefix: String,
+}
+
+impl CommandLineState {
+    /// Handles a key, `history` and `tags` are offered by Up/Down and Tab.
+    pub(crate) fn input(
+        &mut self,
+        key: KeyEvent,
+        history: &[String],
+        tags: &[String],
+    ) -> CommandLineOutcome {
+        match key.code {
+            KeyCode::Esc => return CommandLineOutcome::Cancel,
+            KeyCode::Backspace if self.input.is_empty() => return CommandLineOutcome::Cancel,
+            KeyCode::Enter => return CommandLineOutcome::Run(self.input.text()),
+            KeyCode::Tab => self.complete(tags, true),
+            KeyCode::BackTab => self.complete(tags, false),
+            KeyCode::Up => self.history_previous(history),
+            KeyCode::Down => self.history_next(history),
+            _ => {
+                if !self.input.input(key) {
+                    return CommandLineOutcome::Ignored;
+                }
+                self.completion = None;
+                self.history_index = None;
+            }
+        }
+        CommandLineOutcome::Handled
+    }
+
+    /// The completion candidates and which one is filled in, if Tab found several.
+    pub(crate) fn candidates(&self) -> Option<(&[String], usize)> {
+        self.completion
+            .as_ref()
+            .map(|completion| (completion.candidates.as_slice(), completion.selected))
+    }
+
+    fn complete(&mut self, tags: &[String], forward: bool) {
+        if let Some(completion) = &mut self.completion {
+            let len = completion.candidates.len();
+            completion.selected = if forward {
+                (completion.selected + 1) % len
+            } else {
+                (completion.selected + len - 1) % len
+            };
+            let text = format!(
+                "{}{}",
+                completion.prefix, completion.candidates[completion.selected]
+            );
+            self.input.set_text(&text);
+            return;
+        }
+
+        let text = self.input.text();
+        let word_start = text.rfind(' ').map_or(0, |space| space + 1);
+        let (prefix, word) = text.split_at(word_start);
+        let mut candidates: Vec<String> = argument_candidates(prefix, tags)
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect();
+        candidates.dedup();
+        match candidates.len() {
+            0 => {}
+            1 => self
+                .input
+                .set_text(&format!("{}{} ", prefix, candidates[0])),
+            _ => {
+                self.input.set_text(&format!("{}{}", prefix, candidates[0]));
+                self.completion = Some(Completion {
+                    candidates,
+                    selected: 0,
+                    prefix: prefix.to_string(),
+                });
+            }
+        }
+    }
+
+    fn history_previous(&mut self, history: &[String]) {
+        let index = match self.history_index {
+            _ if history.is_empty() => return,
+            None => {
+                self.draft = self.input.text();
+                history.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        self.completion = None;
+        self.input.set_text(&history[index]);
+    }
+
+    fn history_next(&mut self, history: &[String]) {
+        match self.history_index {
+            Some(index) if index + 1 < history.len() => {
+                self.history_index = Some(index + 1);
+                self.input.set_text(&history[index + 1]);
+            }
+            Some(_) => {
+                self.history_index = None;
+                self.input.set_text(&self.draft);
+            }
+            None => {}
+        }
+        self.completion = None;
+    }
+}
+
+/// Everything that can follow `prefix`, the words typed before the cursor.
+fn argument_candidates(prefix: &str, tags: &[String]) -> Vec<String> {
+    let words: Vec<&str> = prefix.split_whitespace().collect();
+    match words.as_slice() {
+        [] => COMMANDS.iter().map(|(name, _)| name.to_string()).collect(),
+        ["sort"] => SORT_KEYS.iter().map(|key| key.name().to_string()).collect(),
+        ["due"] => ["+1d", "+1w", "-1d"]
+            .iter()
+            .map(|days| days.to_string())
+            .collect(),
+        ["tag"] => tags
+            .iter()
+            .cloned()
+            .chain(tags.iter().map(|tag| format!("-{}", tag)))
+            .collect(),
+        ["export"] => vec!["csv".to_string()],
+        ["snooze"] => ["1h", "3h", "1d", "7d"]
+            .iter()
+            .map(|duration| duration.to_string())
+            .collect(),
+        ["start"] => vec!["none".to_string()],
+        ["remind"] => ["10m", "1h", "1d", "none"]
+            .iter()
+            .map(|before| before.to_string())
+            .collect(),
+        ["estimate"] => ["30m", "1h", "2h", "none"]
+            .iter()
+            .map(|estimate| estimate.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Remembers `command` in `history`, skipping repeats of the last one.
+pub(crate) fn push_history(history: &mut Vec<String>, command: &str) {
+    let command = command.trim();
+    if command.is_empty() || history.last().map(String::as_str) == Some(command) {
+        return;
+    }
+    history.push(command.to_string());
+    if history.len() > MAX_HISTORY {
+        history.remove(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    #[test]
+    fn days() {
+        assert_eq!(parse_days("-1d"), Ok(-1));
+        assert_eq!(parse_days("+3d"), Ok(3));
+        assert_eq!(parse_days("+2w"), Ok(14));
+        assert_eq!(parse_days("5"), Ok(5));
+        assert!(parse_days("soon").is_err());
+        assert!(parse_days(&format!("{}w", i64::MAX)).is_err());
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(
+            parse_duration("1h30m", "estimate"),
+            Ok(Duration::minutes(90))
+        );
+        assert_eq!(parse_duration("2d", "snooze"), Ok(Duration::days(2)));
+        assert_eq!(
+            parse_duration("100000m", "estimate"),
+            Ok(Duration::minutes(100_000))
+        );
+        assert!(parse_duration("100001m", "estimate").is_err());
+        assert!(parse_duration(&format!("{}d", i64::MAX), "snooze").is_err());
+        // a number needs a unit and the total has to be positive
+        assert!(parse_duration("1h30", "estimate").is_err());
+        assert!(parse_duration("0m", "estimate").is_err());
+        assert!(parse_duration("", "estimate").is_err());
+        assert!(parse_duration("h", "estimate").is_err());
+        assert_eq!(
+            parse_duration("1x", "remind"),
+            Err("usage: :remind <time before due, e.g. 1h> | none".to_string())
+        );
+    }
+
+    #[test]
+    fn dates() {
+        let leap_day = Date::from_calendar_date(2024, Month::February, 29).unwrap();
+        assert_eq!(parse_date("2024-02-29"), Ok(leap_day));
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("2024-00-01").is_err());
+        assert!(parse_date("2024-02").is_err());
+        assert!(parse_date("29/02/2024").is_err());
+    }
+
+    #[test]
+    fn commands() {
+        assert!(matches!(":due -1d".parse(), Ok(Command::Due(-1))));
+        assert!(matches!("a  buy milk ".parse(), Ok(Command::Add(title)) if title == "buy milk"));
+        assert!(matches!("tag -work".parse(), Ok(Command::Untag(tag)) if tag == "work"));
+        assert!("tag -".parse::<Command>().is_err());
+        assert!("tag two words".parse::<Command>().is_err());
+        assert!("add".parse::<Command>().is_err());
+        assert!("w now".parse::<Command>().is_err());
+        assert!(matches!(
+            "estimate none".parse(),
+            Ok(Command::Estimate(None))
+        ));
+        assert!(matches!("remind none".parse(), Ok(Command::Remind(None))));
+        assert!(matches!("report".parse(), Ok(Command::Report(None, None))));
+        assert!(matches!(
+            "report 2024-01-01".parse(),
+            Ok(Command::Report(Some(_), None))
+        ));
+        assert!("report 2024-02-01 2024-01-01".parse::<Command>().is_err());
+        assert!("start".parse::<Command>().is_err());
+        assert!(matches!(
+            "export csv out.csv".parse(),
+            Ok(Command::ExportCsv(path)) if path == Path::new("out.csv")
+        ));
+        assert!("export json out.json".parse::<Command>().is_err());
+        assert!("frobnicate".parse::<Command>().is_err());
+        assert!("".parse::<Command>().is_err());
+    }
+
+    fn tab(state: &mut CommandLineState, tags: &[String]) {
+        state.input(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE), &[], tags);
+    }
+
+    #[test]
+    fn completion() {
+        let mut state = CommandLineState::default();
+        state.input.set_text("so");
+        tab(&mut state, &[]);
+        assert_eq!(state.input.text(), "sort ");
+        assert!(state.candidates().is_none());
+
+        // several candidates fill in the first and cycle with Tab
+        tab(&mut state, &[]);
+        assert_eq!(state.input.text(), "sort due");
+        assert_eq!(state.candidates().map(|(all, _)| all.len()), Some(4));
+        tab(&mut state, &[]);
+        assert_eq!(state.input.text(), "sort added");
+        state.input(
+            KeyEvent::new(KeyCode::BackTab, KeyModifiers::NONE),
+            &[],
+            &[],
+        );
+        assert_eq!(state.input.text(), "sort due");
+
+        let tags = vec!["work".to_string(), "web".to_string()];
+        let mut state = CommandLineState::default();
+        state.input.set_text("tag -wo");
+        tab(&mut state, &tags);
+        assert_eq!(state.input.text(), "tag -work ");
+
+        let mut state = CommandLineState::default();
+        state.input.set_text("zzz");
+        tab(&mut state, &[]);
+        assert_eq!(state.input.text(), "zzz");
+    }
+
+    #[test]
+    fn history_skips_repeats() {
+        let mut history = Vec::new();
+        for command in ["w", "w", " ", "q", "w"] {
+            push_history(&mut history, command);
+        }
+        assert_eq!(history, ["w", "q", "w"]);
+    }
+}
@@ -0,0 +1,155 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, StatefulWidget, Table, TableState, Widget},
+};
+
+use crate::{
+    input_keys::{ActionKind, KeyBindings, KeyContext, ACTION_KINDS},
+    theme::Theme,
+};
+
+/// Popup listing every action that can run in `context`, filtered by a fuzzy query.
+pub struct CommandPalette<'a> {
+    pub context: KeyContext,
+    pub theme: &'a Theme,
+    pub key_bindings: &'a KeyBindings,
+}
+
+/// The query typed into the command palette and the highlighted entry.
+#[derive(Default)]
+pub struct CommandPaletteState {
+    pub query: String,
+    /// Index into the filtered actions, clamped when rendered.
+    pub selected: usize,
+}
+
+impl CommandPaletteState {
+    /// The highlighted action, the one Enter runs.
+    pub(crate) fn selected_action(&self, context: KeyContext) -> Option<ActionKind> {
+        let actions = palette_actions(context, &self.query);
+        actions
+            .get(self.selected.min(actions.len().saturating_sub(1)))
+            .copied()
+    }
+}
+
+/// Actions that can run in `context` matching `query`, best match first.
+///
+/// Unlike the help popup this includes actions without a key, so they stay
+/// reachable after their keys are removed in the config file.
+pub(crate) fn palette_actions(context: KeyContext, query: &str) -> Vec<ActionKind> {
+    let mut scored: Vec<(i32, ActionKind)> = ACTION_KINDS
+        .iter()
+        .copied()
+        .filter(|action| *action != ActionKind::CommandPalette)
+        .filter(|action| {
+            action
+                .contexts()
+                .any(|bound| bound == context || bound == KeyContext::Global)
+        })
+        .filter_map(|action| {
+            let name = action.name().replace('_', " ");
+            let score = fuzzy_score(query, &name).max(fuzzy_score(query, action.description()));
+            score.map(|score| (score, action))
+        })
+        .collect();
+    // a stable sort keeps the declaration order between equal scores
+    scored.sort_by_key(|(score, _)| -score);
+    scored.into_iter().map(|(_, action)| action).collect()
+}
+
+/// Scores how well `text` matches `query` as an in-order subsequence,
+/// `None` if some character of the query is missing.
+///
+/// Runs of consecutive characters and matches at the start of a word score
+/// higher, so `sd` ranks "scroll down" above "focus description".
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut previous_matched = false;
+    let mut previous_char = None;
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        let word_start = !previous_char.is_some_and(|previous: char| previous.is_alphanumeric());
+        if ch == wanted {
+            query_chars.next();
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if word_start {
+                score += 3;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = Some(ch);
+    }
+    if query_chars.peek().is_some() {
+        None
+    } else {
+        Some(score)
+    }
+}
+
+impl StatefulWidget for CommandPalette<'_> {
+    type State = CommandPaletteState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut CommandPaletteState) {
+        let b = Block::bordered()
+            .border_style(self.theme.border)
+            .title("Commands")
+            .title_bottom(Line::from(" ↑↓ select  Enter run  Esc close ").italic());
+        let inner = b.inner(area);
+        b.render(area, buf);
+
+        let [query_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        let prompt = if state.query.is_empty() {
+            Line::from("> type to filter").dim()
+        } else {
+            Line::from(format!("> {}", state.query))
+        };
+        Paragraph::new(prompt).render(query_area, buf);
+
+        let actions = palette_actions(self.context, &state.query);
+        state.selected = state.selected.min(actions.len().saturating_sub(1));
+
+        let rows: Vec<(String, &str, String)> = actions
+            .iter()
+            .map(|action| {
+                (
+                    action.name().replace('_', " "),
+                    action.description(),
+                    self.key_bindings.keys_text_in(self.context, *action),
+                )
+            })
+            .collect();
+        let name_width = rows.iter().map(|(name, _, _)| name.len()).max();
+        let key_width = rows.iter().map(|(_, _, keys)| keys.len()).max();
+        let widths = [
+            Constraint::Length(name_width.unwrap_or(0) as u16),
+            Constraint::Fill(1),
+            Constraint::Length(key_width.unwrap_or(0) as u16),
+        ];
+        let rows = rows.into_iter().map(|(name, description, keys)| {
+            Row::new(vec![
+                Cell::from(name).bold(),
+                Cell::from(description),
+                Cell::from(keys).dim(),
+            ])
+        });
+        let table = Table::new(rows, widths)
+            .column_spacing(2)
+            .highlight_style(self.theme.selected);
+        let mut table_state = TableState::default()
+            .with_selected(Some(state.selected).filter(|_| !actions.is_empty()));
+        StatefulWidget::render(table, list_area, buf, &mut table_state);
+    }
+}
@@ -0,0 +1,59 @@
+use std::{collections::BTreeMap, fs, io};
+
+use serde::Deserialize;
+
+use crate::{
+    dates::DateConfig, input_keys::KeyConfig, panes::LayoutConfig, pomodoro::PomodoroConfig,
+    reminders::ReminderConfig, theme::ThemeConfig,
+};
+
+pub(crate) const CONFIG_PATH: &str = "config.toml";
+
+/// User settings read from `config.toml` at startup.
+///
+/// Every section is optional, missing values fall back to their defaults.
+/// The sections are checked when they are built, with every problem of a
+/// section collected so a broken config can be fixed in one go.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub editor: EditorConfig,
+    /// Action names mapped to their keys, replacing the default keys of that action.
+    pub keys: BTreeMap<String, KeyConfig>,
+    pub theme: ThemeConfig,
+    pub layout: LayoutConfig,
+    pub dates: DateConfig,
+    pub shuffle: ShuffleConfig,
+    pub pomodoro: PomodoroConfig,
+    pub reminders: ReminderConfig,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct EditorConfig {
+    /// Use vim style modal editing inside the title and description fields.
+    pub vim_mode: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ShuffleConfig {
+    /// Pick one of the overdue tasks when there are any.
+    pub favour_overdue: bool,
+    /// How long the snooze key defers a task.
+    pub snooze_hours: i64,
+}
+
+impl Default for ShuffleConfig {
+    fn default() -> Self {
+        ShuffleConfig {
+            favour_overdue: false,
+            snooze_hours: 24,
+        }
+    }
+}
+
+pub(crate) fn load_config() -> io::Result<Config> {
+    let contents = fs::read_to_string(CONFIG_PATH)?;
+    toml::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,163 @@
+use std::{fs, io, thread, time::Duration, time::SystemTime};
+
+use time::OffsetDateTime;
+
+use crate::{
+    config::{self, Config},
+    reminders::{self, Reminders},
+    task::Task,
+    DATA_PATH,
+};
+
+/// How often the data file and the reminders are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Sends the reminders of the saved tasks through the notifier command
+/// without the TUI, until the process is stopped.
+pub(crate) fn run() -> io::Result<()> {
+    let config = match config::load_config() {
+        Ok(config) => config,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Config::default(),
+        Err(e) => {
+            return Err(io::Error::new(
+                e.kind(),
+                format!("{}: {}", config::CONFIG_PATH, e),
+            ))
+        }
+    };
+    let reminders = Reminders::new(&config.reminders);
+    if reminders.notifier.is_none() {
+        return Err(io::Error::other(format!(
+            "--daemon needs a `command` in the [reminders] section of {}",
+            config::CONFIG_PATH
+        )));
+    }
+
+    eprintln!("watching {} for reminders", DATA_PATH);
+    let mut daemon = Daemon {
+        started: reminders.clock.now(),
+        reminders,
+        tasks: Vec::new(),
+        modified: None,
+    };
+    loop {
+        daemon.poll();
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// The tasks as last read from the data file.
+struct Daemon {
+    reminders: Reminders,
+    /// Reminders due before then are not sent, they were missed or sent by
+    /// an earlier run.
+    started: OffsetDateTime,
+    tasks: Vec<Task>,
+    /// When the data file was read, it is read again once that changes.
+    modified: Option<SystemTime>,
+}
+
+impl Daemon {
+    fn poll(&mut self) {
+        let modified = fs::metadata(DATA_PATH)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified != self.modified {
+            match crate::load_from_disk() {
+                Ok(app) => {
+                    self.reload(app.all_tasks().cloned().collect());
+                    self.modified = modified;
+                }
+                // possibly caught halfway through a save, tried again on the next poll
+                Err(e) => eprintln!("could not read {}: {}", DATA_PATH, e),
+            }
+        }
+
+        let now = self.reminders.clock.now();
+        let notices = reminders::take_due(self.tasks.iter_mut(), now);
+        if let Some(notifier) = &mut self.reminders.notifier {
+            if let Err(e) = notifier.finished() {
+                eprintln!("could not send a reminder: {}", e);
+            }
+            for notice in &notices {
+                if let Err(e) = notifier.notify(notice) {
+                    eprintln!("could not send a reminder for {}: {}", notice.title, e);
+                }
+            }
+        }
+    }
+
+    /// Takes the tasks of the data file, keeping what went off already.
+    ///
+    /// The daemon does not write the file, so the reminders it sent are only
+    /// remembered here.
+    fn reload(&mut self, mut tasks: Vec<Task>) {
+        for task in &mut tasks {
+            // overdue tasks are worth a notice even without a reminder
+            task.add_reminder(time::Duration::ZERO);
+            let old = self.tasks.iter().find(|old| old.id == task.id);
+            for reminder in &mut task.reminders {
+                if task.due_time - reminder.before < self.started {
+                    reminder.fired_for = Some(task.due_time);
+                }
+                let fired = old
+                    .and_then(|old| {
+                        old.reminders
+                            .iter()
+                            .find(|old| old.before == reminder.before)
+                    })
+                    .and_then(|old| old.fired_for);
+                reminder.fired_for = reminder.fired_for.max(fired);
+            }
+        }
+        self.tasks = tasks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    const STARTED: OffsetDateTime = datetime!(2024-03-01 12:00 UTC);
+
+    fn task_due_at(title: &str, due_time: OffsetDateTime) -> Task {
+        let mut task = Task::default(title.to_string());
+        task.due_time = due_time;
+        task
+    }
+
+    fn sent(daemon: &mut Daemon, now: OffsetDateTime) -> Vec<String> {
+        reminders::take_due(daemon.tasks.iter_mut(), now)
+            .into_iter()
+            .map(|notice| notice.title)
+            .collect()
+    }
+
+    #[test]
+    fn reminders_due_before_the_start_are_skipped() {
+        let mut daemon = Daemon {
+            reminders: Reminders::default(),
+            started: STARTED,
+            tasks: Vec::new(),
+            modified: None,
+        };
+        let mut early = task_due_at("early", STARTED + time::Duration::HOUR);
+        early.add_reminder(time::Duration::hours(2));
+        let tasks = vec![
+            task_due_at("overdue", STARTED - time::Duration::DAY),
+            early,
+            task_due_at("later", STARTED + time::Duration::HOUR),
+        ];
+        daemon.reload(tasks.clone());
+        assert!(sent(&mut daemon, STARTED).is_empty());
+
+        let due = STARTED + time::Duration::HOUR;
+        assert_eq!(sent(&mut daemon, due), ["early", "later"]);
+
+        // what was sent is kept when the file is read again
+        daemon.reload(tasks);
+        assert!(sent(&mut daemon, due).is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use serde::Deserialize;
+use time::{Date, Duration, OffsetDateTime};
+
+/// How dates are written out.
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    /// `2026-10-19`
+    #[default]
+    Iso,
+    /// `10/19/2026`
+    Us,
+    /// `19/10/2026`
+    Eu,
+    /// `Mon 19 Oct 2026`
+    Long,
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+pub enum Clock {
+    /// `14:05`
+    #[default]
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+    /// `2:05 PM`
+    #[serde(rename = "12h")]
+    TwelveHour,
+}
+
+/// The `[dates]` config section.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct DateConfig {
+    pub date_format: DateFormat,
+    pub clock: Clock,
+    /// Add "in 2 days" or "3h ago" next to dates.
+    pub relative: bool,
+}
+
+impl Default for DateConfig {
+    fn default() -> Self {
+        DateConfig {
+            date_format: DateFormat::default(),
+            clock: Clock::default(),
+            relative: true,
+        }
+    }
+}
+
+impl DateConfig {
+    pub(crate) fn format_date(&self, date: Date) -> String {
+        let (year, month, day) = (date.year(), u8::from(date.month()), date.day());
+        match self.date_format {
+            DateFormat::Iso => format!("{}-{:02}-{:02}", year, month, day),
+            DateFormat::Us => format!("{:02}/{:02}/{}", month, day, year),
+            DateFormat::Eu => format!("{:02}/{:02}/{}", day, month, year),
+            DateFormat::Long => format!(
+                "{} {} {} {}",
+                &date.weekday().to_string()[..3],
+                day,
+                &date.month().to_string()[..3],
+                year
+            ),
+        }
+    }
+
+    pub(crate) fn format_clock(&self, time: OffsetDateTime) -> String {
+        match self.clock {
+            Clock::TwentyFourHour => format!("{:02}:{:02}", time.hour(), time.minute()),
+            Clock::TwelveHour => {
+                let suffix = if time.hour() < 12 { "AM" } else { "PM" };
+                let hour = match time.hour() % 12 {
+                    0 => 12,
+                    hour => hour,
+                };
+                format!("{}:{:02} {}", hour, time.minute(), suffix)
+            }
+        }
+    }
+
+    /// The date followed by the time of day.
+    pub(crate) fn format_date_time(&self, time: OffsetDateTime) -> String {
+        format!(
+            "{} {}",
+            self.format_date(time.date()),
+            self.format_clock(time)
+        )
+    }
+}
+
+/// Today in the local time zone, the one due dates are stored in.
+pub(crate) fn today() -> Date {
+    OffsetDateTime::now_local()
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .date()
+}
+
+/// How far `time` is from `now`, e.g. `in 2 days` or `3h ago`.
+pub(crate) fn relative_time(time: OffsetDateTime, now: OffsetDateTime) -> String {
+    let difference = time - now;
+    let span = span_text(difference.abs());
+    match span {
+        None => "just now".to_string(),
+        Some(span) if difference.is_negative() => format!("{} ago", span),
+        Some(span) => format!("in {}", span),
+    }
+}
+
+/// Relative text for a due date, `overdue by 1 day` once it has passed
+/// and `overdue` is set.
+pub(crate) fn relative_due(due_time: OffsetDateTime, now: OffsetDateTime, overdue: bool) -> String {
+    let difference = due_time - now;
+    match span_text(difference.abs()) {
+        None => "due now".to_string(),
+        Some(span) if overdue => format!("overdue by {}", span),
+        Some(span) if difference.is_negative() => format!("was due {} ago", span),
+        Some(span) => format!("due in {}", span),
+    }
+}
+
+/// A duration in hours and minutes, e.g. `1h 20m`.
+pub(crate) fn duration_text(duration: Duration) -> String {
+    let minutes = duration.whole_minutes();
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+/// A duration in its largest whole unit, `None` under a minute.
+pub(crate) fn span_text(duration: Duration) -> Option<String> {
+    let plural = |n: i64, unit: &str| {
+        if n == 1 {
+            format!("1 {}", unit)
+        } else {
+            format!("{} {}s", n, unit)
+        }
+    };
+    let text = if duration < Duration::MINUTE {
+        return None;
+    } else if duration < Duration::HOUR {
+        format!("{}m", duration.whole_minutes())
+    } else if duration < Duration::DAY {
+        format!("{}h", duration.whole_hours())
+    } else if duration < Duration::weeks(2) {
+        plural(duration.whole_days(), "day")
+    } else if duration < Duration::days(60) {
+        plural(duration.whole_weeks(), "week")
+    } else if duration < Duration::days(365) {
+        plural(duration.whole_days() / 30, "month")
+    } else {
+        plural(duration.whole_days() / 365, "year")
+    };
+    Some(text)
+}
@@ -0,0 +1,51 @@
+use std::{fs::File, io::Write, path::Path};
+
+use time::OffsetDateTime;
+
+use crate::task::Task;
+
+/// Writes the tasks to `path` as CSV with a header row.
+pub(crate) fn export_csv<'a>(
+    tasks: impl Iterator<Item = &'a Task>,
+    path: &Path,
+) -> std::io::Result<()> {
+    let mut f = File::create(path)?;
+    writeln!(
+        f,
+        "title,description,status,added,edited,due,completed,tags"
+    )?;
+    for task in tasks {
+        let fields = [
+            task.title.clone(),
+            task.description.clone().unwrap_or_default(),
+            task.task_status.to_text(),
+            format_time(task.time_added),
+            format_time(task.time_edited),
+            format_time(task.due_time),
+            task.time_completed.map(format_time).unwrap_or_default(),
+            task.tags.join(" "),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        writeln!(f, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+fn format_time(time: OffsetDateTime) -> String {
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        time.date(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
+
+/// Quotes a field if it contains anything that would break the row.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
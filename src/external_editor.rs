@@ -0,0 +1,60 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process::{self, Command},
+};
+
+/// Opens `text` in `$VISUAL` or `$EDITOR` and returns the edited text.
+///
+/// The terminal has to be handed back to the shell before calling this, the
+/// editor takes over stdin and stdout until it exits.
+pub(crate) fn edit_in_external_editor(text: &str) -> io::Result<String> {
+    // the random part keeps other users from guessing the name in the shared directory
+    let path = env::temp_dir().join(format!(
+        "tui_todo_{}_{:016x}.md",
+        process::id(),
+        rand::random::<u64>()
+    ));
+    create_private(&path)?.write_all(text.as_bytes())?;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // allow editors that need flags, e.g. `code --wait`
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args.next().unwrap_or("vi");
+
+    let result = match Command::new(program).args(editor_args).arg(&path).status() {
+        Ok(status) if status.success() => fs::read_to_string(&path),
+        Ok(status) => Err(io::Error::other(format!(
+            "{} exited with {}",
+            program, status
+        ))),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("could not start {}: {}", program, e),
+        )),
+    };
+    let _ = fs::remove_file(&path);
+
+    // editors usually end the file with a newline that was not in the field
+    result.map(|edited| match edited.strip_suffix('\n') {
+        Some(stripped) => stripped.strip_suffix('\r').unwrap_or(stripped).to_string(),
+        None => edited,
+    })
+}
+
+/// Creates a file only the current user can read, failing if anything,
+/// e.g. a symlink planted by someone else, is at `path` already.
+fn create_private(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
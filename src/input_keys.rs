@@ -1,38 +1,48 @@
-use crossterm::event::KeyCode;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
-macro_rules! const_action_kinds {
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+macro_rules! action_kinds {
     (
         $(
-            ($keycode_name:ident, $const_name:ident) { $variant:ident, $key_code:expr, $name:expr, $description:expr }
+            $variant:ident { $name:expr, $default_keys:expr, $description:expr }
         ),*
     ) => {
-        $(
-            // Define a constant for the KeyCode
-            pub(crate) const $keycode_name: KeyCode = $key_code;
-
-
-            // Define a constant for each ActionKind variant with InputKey
-            pub(crate) const $const_name: ActionKind = ActionKind::$variant(InputKey {
-                key_code: $key_code,
-                name: $name,
-                description: $description,
-            });
-        )*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub(crate) enum ActionKind {
+            $($variant),*
+        }
 
         // Define a constant array containing all ActionKind variants
-        pub(crate) const ACTION_KINDS: [ActionKind; count!($($const_name),*)] = [
+        pub(crate) const ACTION_KINDS: [ActionKind; count!($($variant),*)] = [
             $(
-                $const_name
+                ActionKind::$variant
             ),*
         ];
 
-      // Define a function that maps KeyCode to ActionKind
-        pub(crate) fn keycode_to_actionkind(keycode: KeyCode) -> Option<ActionKind> {
-            match keycode {
-                $(
-                    $keycode_name => Some($const_name),
-                )*
-                _ => None, // Default case when keycode does not match
+        impl ActionKind {
+            /// The name of the action in the `[keys]` section of the config file.
+            pub(crate) const fn name(self) -> &'static str {
+                match self {
+                    $(ActionKind::$variant => $name,)*
+                }
+            }
+
+            pub(crate) const fn description(self) -> &'static str {
+                match self {
+                    $(ActionKind::$variant => $description,)*
+                }
+            }
+
+            /// Keys bound to the action when the config file does not say otherwise.
+            ///
+            /// The contexts listed here are also the only ones the action can be
+            /// bound in, the first one is where a plain `[keys]` entry binds it.
+            const fn default_keys(self) -> &'static [(KeyContext, &'static str)] {
+                match self {
+                    $(ActionKind::$variant => $default_keys,)*
+                }
             }
         }
     }
@@ -51,61 +61,661 @@ macro_rules! count_expr {
     };
 }
 
-const_action_kinds! {
-    (ADD_TASK_KEYCODE, ADD_TASK) { AddTask, KeyCode::Char('a'), "a", "Add a new task" },
-    (EDIT_MODE_KEYCODE, EDIT_MODE){ EditMode, KeyCode::Char('e'), "e", "Enter edit mode for current task" },
-    (CHOOSE_TASK_KEYCODE , CHOOSE_TASK ){ ShuffleTasks, KeyCode::Char('r'), "r", "Choose a task to display"},
-    (QUIT_KEYCODE , QUIT ){ Quit, KeyCode::Char('q'), "q", "Quit"},
-    (MARK_COMPLETE_KEYCODE , MARK_COMPLETE ){MarkTaskDone, KeyCode::Char('d'), "d", "Mark task as done"},
-    (MARK_INCOMPLETE, MARK_INCOMPLETE_KEYCODE ) { MarkTaskInProgress, KeyCode::Char('D'), "D", "Mark task as in progress"},
-    (KEYS_HINT,  KEYS_HINT_KEYCODE ){ KeysHint, KeyCode::Char('?'), "?", "Display this help page"},
-    (FOCUS_TITLE,  FOCUS_TITLE_KEYCODE ){ FocusTitle, KeyCode::Char('t'), "t", "Focus the title field"},
-    (FOCUS_DESCRIPTION,  FOCUS_DESCRIPTION_KEYCODE ){ FocusDescription, KeyCode::Char('T'), "T", "Focus the description field" },
-    (CHANGE_MODE,  CHANGE_MODE_KEYCODE ){ ChangeMode, KeyCode::Esc, "Esc", "Change modes" },
-    (INCREMENT_DATE,  INCREMENT_DATE_KEYCODE ){ IncrementDueDate, KeyCode::Char('y'), "y", "Increase the due date by 1"},
-    (DECREMENT_DATE,  DECREMENT_DATE_KEYCODE ){ DecrementDueDate, KeyCode::Char('Y'), "Y", "Decrease the due date by 1"}
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd)]
-pub(crate) enum ActionKind {
-    AddTask(InputKey),
-    EditMode(InputKey),
-    ShuffleTasks(InputKey),
-    Quit(InputKey),
-    MarkTaskDone(InputKey),
-    MarkTaskInProgress(InputKey),
-    KeysHint(InputKey),
-    FocusTitle(InputKey),
-    FocusDescription(InputKey),
-    ChangeMode(InputKey),
-    IncrementDueDate(InputKey),
-    DecrementDueDate(InputKey),
+action_kinds! {
+    AddTask { "add_task", &[(KeyContext::Main, "a")], "Add a new task" },
+    EditMode { "edit_task", &[(KeyContext::Main, "e"), (KeyContext::Agenda, "e"), (KeyContext::Planner, "e"), (KeyContext::Board, "e")], "Enter edit mode for current task" },
+    ShuffleTasks { "choose_task", &[(KeyContext::Main, "r")], "Choose a task to display" },
+    Quit { "quit", &[(KeyContext::Main, "q"), (KeyContext::Global, "ctrl-c")], "Quit" },
+    MarkTaskDone { "mark_done", &[(KeyContext::Main, "d"), (KeyContext::Agenda, "d"), (KeyContext::Planner, "d"), (KeyContext::Board, "d")], "Mark task as done" },
+    MarkTaskInProgress { "mark_in_progress", &[(KeyContext::Main, "D")], "Mark task as in progress" },
+    KeysHint { "help", &[(KeyContext::Main, "?"), (KeyContext::Editing, "?"), (KeyContext::Calendar, "?"), (KeyContext::Agenda, "?"), (KeyContext::Planner, "?"), (KeyContext::Board, "?"), (KeyContext::Dashboard, "?")], "Display this help page" },
+    FocusTitle { "focus_title", &[(KeyContext::Editing, "t")], "Focus the title field" },
+    FocusDescription { "focus_description", &[(KeyContext::Editing, "T")], "Focus the description field" },
+    ChangeMode { "change_mode", &[(KeyContext::Global, "esc")], "Change modes" },
+    SaveTask { "save", &[(KeyContext::Global, "ctrl-s"), (KeyContext::Editing, "a")], "Save the task being edited, or all tasks to disk" },
+    IncrementDueDate { "increment_due_date", &[(KeyContext::Editing, "y"), (KeyContext::Agenda, "y")], "Increase the due date by 1" },
+    DecrementDueDate { "decrement_due_date", &[(KeyContext::Editing, "Y"), (KeyContext::Agenda, "Y")], "Decrease the due date by 1" },
+    OpenEditor { "open_editor", &[(KeyContext::Editing, "E")], "Edit the description in $EDITOR" },
+    ScrollDown { "scroll_down", &[(KeyContext::Main, "j")], "Scroll the description down" },
+    ScrollUp { "scroll_up", &[(KeyContext::Main, "k")], "Scroll the description up" },
+    CommandPalette { "command_palette", &[(KeyContext::Global, "ctrl-p")], "Search and run any action" },
+    CommandLine { "command_line", &[(KeyContext::Main, ":")], "Enter a command like :due +3d" },
+    ToggleListPane { "toggle_list", &[(KeyContext::Main, "p l")], "Show or hide the task list" },
+    ToggleDetailPane { "toggle_detail", &[(KeyContext::Main, "p d")], "Show or hide the task details" },
+    ToggleCalendarPane { "toggle_calendar", &[(KeyContext::Main, "p c")], "Show or hide the calendar" },
+    ToggleAgendaPane { "toggle_agenda", &[(KeyContext::Main, "p a")], "Show or hide the agenda" },
+    ToggleStatsPane { "toggle_stats", &[(KeyContext::Main, "p s")], "Show or hide the statistics" },
+    FocusNextPane { "next_pane", &[(KeyContext::Main, "tab")], "Focus the next pane" },
+    GrowPane { "grow_pane", &[(KeyContext::Main, "+")], "Make the focused pane bigger" },
+    ShrinkPane { "shrink_pane", &[(KeyContext::Main, "-")], "Make the focused pane smaller" },
+    OpenCalendar { "open_calendar", &[(KeyContext::Main, "c")], "Open the calendar of every due date" },
+    OpenAgenda { "open_agenda", &[(KeyContext::Main, "A")], "Open the agenda of unfinished tasks" },
+    OpenPlanner { "open_planner", &[(KeyContext::Main, "W")], "Open the week planner" },
+    OpenBoard { "open_board", &[(KeyContext::Main, "B")], "Open the board of tasks by status" },
+    OpenDashboard { "open_dashboard", &[(KeyContext::Main, "S")], "Open the statistics dashboard" },
+    ToggleFocusTimer { "focus_timer", &[(KeyContext::Main, "f")], "Start or stop the focus timer on the shown task" },
+    Snooze { "snooze", &[(KeyContext::Main, "z"), (KeyContext::Agenda, "z"), (KeyContext::Board, "z")], "Defer the task for the configured hours" },
+    ToggleTimeTracking { "track_time", &[(KeyContext::Main, "t"), (KeyContext::Agenda, "t"), (KeyContext::Board, "t")], "Start or stop tracking time on the task" },
+    MoveLeft { "move_left", &[(KeyContext::Calendar, "h"), (KeyContext::Calendar, "left"), (KeyContext::Planner, "h"), (KeyContext::Planner, "left"), (KeyContext::Board, "h"), (KeyContext::Board, "left")], "Move the selection left" },
+    MoveRight { "move_right", &[(KeyContext::Calendar, "l"), (KeyContext::Calendar, "right"), (KeyContext::Planner, "l"), (KeyContext::Planner, "right"), (KeyContext::Board, "l"), (KeyContext::Board, "right")], "Move the selection right" },
+    MoveUp { "move_up", &[(KeyContext::Calendar, "k"), (KeyContext::Calendar, "up"), (KeyContext::Agenda, "k"), (KeyContext::Agenda, "up"), (KeyContext::Planner, "k"), (KeyContext::Planner, "up"), (KeyContext::Board, "k"), (KeyContext::Board, "up")], "Move the selection up" },
+    MoveDown { "move_down", &[(KeyContext::Calendar, "j"), (KeyContext::Calendar, "down"), (KeyContext::Agenda, "j"), (KeyContext::Agenda, "down"), (KeyContext::Planner, "j"), (KeyContext::Planner, "down"), (KeyContext::Board, "j"), (KeyContext::Board, "down")], "Move the selection down" },
+    PreviousPage { "previous_page", &[(KeyContext::Calendar, "["), (KeyContext::Calendar, "pageup"), (KeyContext::Planner, "["), (KeyContext::Planner, "pageup")], "Go to the previous month or week" },
+    NextPage { "next_page", &[(KeyContext::Calendar, "]"), (KeyContext::Calendar, "pagedown"), (KeyContext::Planner, "]"), (KeyContext::Planner, "pagedown")], "Go to the next month or week" },
+    GoToToday { "today", &[(KeyContext::Calendar, "t"), (KeyContext::Planner, "t")], "Select today" },
+    MoveTaskLeft { "move_task_left", &[(KeyContext::Planner, "H"), (KeyContext::Planner, "shift-left"), (KeyContext::Board, "H"), (KeyContext::Board, "shift-left")], "Move the selected task to the day or column before" },
+    MoveTaskRight { "move_task_right", &[(KeyContext::Planner, "L"), (KeyContext::Planner, "shift-right"), (KeyContext::Board, "L"), (KeyContext::Board, "shift-right")], "Move the selected task to the day or column after" },
+    MoveTaskUp { "move_task_up", &[(KeyContext::Planner, "K"), (KeyContext::Planner, "shift-up")], "Move the selected task up within its day" },
+    MoveTaskDown { "move_task_down", &[(KeyContext::Planner, "J"), (KeyContext::Planner, "shift-down")], "Move the selected task down within its day" }
+}
+
+impl FromStr for ActionKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ACTION_KINDS
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| format!("unknown action `{}`", name))
+    }
+}
+
+/// Where a key binding applies.
+///
+/// `Global` bindings work everywhere, including while typing into a field,
+/// so they can not be plain characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum KeyContext {
+    Global,
+    /// The main screen showing the current task.
+    Main,
+    /// The editing screen while no field has focus.
+    Editing,
+    /// Typing into the title or description, only global bindings apply.
+    TextField,
+    /// The calendar screen.
+    Calendar,
+    /// The agenda screen.
+    Agenda,
+    /// The week planner.
+    Planner,
+    /// The board of tasks by status.
+    Board,
+    /// The statistics dashboard.
+    Dashboard,
+}
+
+impl KeyContext {
+    /// The name of the `[keys.<context>]` section in the config file.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            KeyContext::Global => "global",
+            KeyContext::Main => "main",
+            KeyContext::Editing => "editing",
+            KeyContext::TextField => "text_field",
+            KeyContext::Calendar => "calendar",
+            KeyContext::Agenda => "agenda",
+            KeyContext::Planner => "planner",
+            KeyContext::Board => "board",
+            KeyContext::Dashboard => "dashboard",
+        }
+    }
+
+    fn overlaps(self, other: KeyContext) -> bool {
+        self == other || self == KeyContext::Global || other == KeyContext::Global
+    }
+}
+
+impl FromStr for KeyContext {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [
+            KeyContext::Global,
+            KeyContext::Main,
+            KeyContext::Editing,
+            KeyContext::Calendar,
+            KeyContext::Agenda,
+            KeyContext::Planner,
+            KeyContext::Board,
+            KeyContext::Dashboard,
+        ]
+        .iter()
+        .copied()
+        .find(|context| context.name() == name)
+        .ok_or_else(|| format!("unknown key context `{}`", name))
+    }
+}
+
+/// Groups actions under a heading in the help popup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ActionCategory {
+    Tasks,
+    Editing,
+    Navigation,
+    Panes,
+    General,
+}
+
+impl ActionCategory {
+    pub(crate) const fn to_text(self) -> &'static str {
+        match self {
+            ActionCategory::Tasks => "Tasks",
+            ActionCategory::Editing => "Editing",
+            ActionCategory::Navigation => "Navigation",
+            ActionCategory::Panes => "Panes",
+            ActionCategory::General => "General",
+        }
+    }
 }
 
 impl ActionKind {
-    pub(crate) const fn input_key(&self) -> &InputKey {
-        match &self {
-            ActionKind::AddTask(input_key) => input_key,
-            ActionKind::EditMode(input_key) => input_key,
-            ActionKind::ShuffleTasks(input_key) => input_key,
-            ActionKind::Quit(input_key) => input_key,
-            ActionKind::MarkTaskDone(input_key) => input_key,
-            ActionKind::MarkTaskInProgress(input_key) => input_key,
-            ActionKind::KeysHint(input_key) => input_key,
-            ActionKind::FocusTitle(input_key) => input_key,
-            ActionKind::FocusDescription(input_key) => input_key,
-            ActionKind::ChangeMode(input_key) => input_key,
-            ActionKind::IncrementDueDate(input_key) => input_key,
-            ActionKind::DecrementDueDate(input_key) => input_key,
+    pub(crate) const fn category(self) -> ActionCategory {
+        match self {
+            ActionKind::AddTask
+            | ActionKind::EditMode
+            | ActionKind::ShuffleTasks
+            | ActionKind::MarkTaskDone
+            | ActionKind::MarkTaskInProgress
+            | ActionKind::ToggleFocusTimer
+            | ActionKind::ToggleTimeTracking
+            | ActionKind::Snooze => ActionCategory::Tasks,
+            ActionKind::FocusTitle
+            | ActionKind::FocusDescription
+            | ActionKind::SaveTask
+            | ActionKind::IncrementDueDate
+            | ActionKind::DecrementDueDate
+            | ActionKind::OpenEditor
+            | ActionKind::MoveTaskLeft
+            | ActionKind::MoveTaskRight
+            | ActionKind::MoveTaskUp
+            | ActionKind::MoveTaskDown => ActionCategory::Editing,
+            ActionKind::ScrollDown
+            | ActionKind::ScrollUp
+            | ActionKind::ChangeMode
+            | ActionKind::OpenCalendar
+            | ActionKind::OpenAgenda
+            | ActionKind::OpenPlanner
+            | ActionKind::OpenBoard
+            | ActionKind::OpenDashboard
+            | ActionKind::MoveLeft
+            | ActionKind::MoveRight
+            | ActionKind::MoveUp
+            | ActionKind::MoveDown
+            | ActionKind::PreviousPage
+            | ActionKind::NextPage
+            | ActionKind::GoToToday => ActionCategory::Navigation,
+            ActionKind::ToggleListPane
+            | ActionKind::ToggleDetailPane
+            | ActionKind::ToggleCalendarPane
+            | ActionKind::ToggleAgendaPane
+            | ActionKind::ToggleStatsPane
+            | ActionKind::FocusNextPane
+            | ActionKind::GrowPane
+            | ActionKind::ShrinkPane => ActionCategory::Panes,
+            ActionKind::KeysHint
+            | ActionKind::CommandPalette
+            | ActionKind::CommandLine
+            | ActionKind::Quit => ActionCategory::General,
         }
     }
+
+    /// The contexts the action can be bound in.
+    pub(crate) fn contexts(self) -> impl Iterator<Item = KeyContext> {
+        let mut contexts: Vec<KeyContext> = self
+            .default_keys()
+            .iter()
+            .map(|(context, _)| *context)
+            .collect();
+        contexts.dedup();
+        contexts.into_iter()
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd)]
-pub(crate) struct InputKey {
-    pub(crate) key_code: KeyCode,
-    pub(crate) name: &'static str,
-    pub(crate) description: &'static str,
+/// A single key together with its modifiers, e.g. `ctrl-s`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct KeyPress {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        // shift is already part of the character, `D` rather than `shift-d`
+        let shift_in_code = matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab);
+        if key.modifiers.contains(KeyModifiers::SHIFT) && !shift_in_code {
+            modifiers |= KeyModifiers::SHIFT;
+        }
+        KeyPress {
+            code: key.code,
+            modifiers,
+        }
+    }
+}
+
+impl FromStr for KeyPress {
+    type Err = String;
+
+    /// Parses keys like `a`, `?`, `esc`, `ctrl-s`, `alt-enter` or `shift-d`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n)) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{}`", s)),
+                },
+            },
+        };
+
+        // fold shift into the key the same way crossterm reports it
+        let code = match code {
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers -= KeyModifiers::SHIFT;
+                KeyCode::Char(ch.to_ascii_uppercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers -= KeyModifiers::SHIFT;
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Ok(KeyPress { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(ch) => write!(f, "{}", ch),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            code => write!(f, "{}", code),
+        }
+    }
+}
+
+/// A sequence of keys pressed one after another, e.g. `g g`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct KeyChord(Vec<KeyPress>);
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(KeyPress::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err("empty key binding".to_string());
+        }
+        Ok(KeyChord(keys))
+    }
+}
+
+impl KeyChord {
+    /// Whether the chord starts with a key that would otherwise be typed.
+    fn starts_with_char(&self) -> bool {
+        let first = self.0[0];
+        matches!(first.code, KeyCode::Char(_))
+            && !first
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, key) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", key)?;
+        }
+        Ok(())
+    }
+}
+
+/// The keys for one action in the config file, either `"a"` or `["a", "ctrl-n"]`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// An entry of the `[keys]` config section.
+///
+/// Either keys for the action in its first context, `quit = "q"`, or a
+/// `[keys.<context>]` table of actions bound in that context.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum KeyConfig {
+    Keys(KeySpec),
+    Context(BTreeMap<String, KeySpec>),
+}
+
+impl KeySpec {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeySpec::One(key) if key.is_empty() => Vec::new(),
+            KeySpec::One(key) => vec![key.as_str()],
+            KeySpec::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// How the keys pressed so far relate to the bindings.
+#[derive(PartialEq, Eq)]
+pub(crate) enum KeyMatch {
+    Action(ActionKind),
+    /// The keys are the start of a longer chord, wait for the next key.
+    Pending,
+    None,
+}
+
+/// The live key bindings, the defaults merged with the `[keys]` config section.
+pub(crate) struct KeyBindings {
+    bindings: Vec<(KeyContext, KeyChord, ActionKind)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings::new(&BTreeMap::new()).expect("the default key bindings are valid")
+    }
+}
+
+impl KeyBindings {
+    /// Builds the bindings, with the config replacing the default keys of an
+    /// action in a context.
+    pub(crate) fn new(config: &BTreeMap<String, KeyConfig>) -> Result<KeyBindings, String> {
+        let mut errors = Vec::new();
+        let mut overrides: BTreeMap<(KeyContext, ActionKind), Vec<String>> = BTreeMap::new();
+        let mut add_override = |context: Option<KeyContext>, name: &str, spec: &KeySpec| {
+            let action = ActionKind::from_str(name)?;
+            let context = context.unwrap_or(action.default_keys()[0].0);
+            if !action.contexts().any(|valid| valid == context) {
+                return Err(format!(
+                    "{} can not be bound in the {} context",
+                    name,
+                    context.name()
+                ));
+            }
+            let keys = spec.keys().into_iter().map(str::to_string).collect();
+            overrides.insert((context, action), keys);
+            Ok(())
+        };
+        for (name, entry) in config {
+            let result = match entry {
+                KeyConfig::Keys(spec) => add_override(None, name, spec),
+                KeyConfig::Context(actions) => KeyContext::from_str(name).and_then(|context| {
+                    actions
+                        .iter()
+                        .try_for_each(|(action, spec)| add_override(Some(context), action, spec))
+                }),
+            };
+            if let Err(e) = result {
+                errors.push(e);
+            }
+        }
+
+        let mut bindings = Vec::new();
+        for action in ACTION_KINDS {
+            for context in action.contexts() {
+                let keys = match overrides.get(&(context, action)) {
+                    Some(keys) => keys.clone(),
+                    None => action
+                        .default_keys()
+                        .iter()
+                        .filter(|(default_context, _)| *default_context == context)
+                        .map(|(_, key)| key.to_string())
+                        .collect(),
+                };
+                for key in keys {
+                    match KeyChord::from_str(&key) {
+                        Ok(chord) if context == KeyContext::Global && chord.starts_with_char() => {
+                            errors.push(format!(
+                                "{}: global binding `{}` would get in the way of typing",
+                                action.name(),
+                                chord
+                            ))
+                        }
+                        Ok(chord) => bindings.push((context, chord, action)),
+                        Err(e) => errors.push(format!("{}: {}", action.name(), e)),
+                    }
+                }
+            }
+        }
+
+        for (i, (context, chord, action)) in bindings.iter().enumerate() {
+            for (other_context, other_chord, other_action) in &bindings[i + 1..] {
+                let shorter = chord.0.len().min(other_chord.0.len());
+                if context.overlaps(*other_context)
+                    && chord.0[..shorter] == other_chord.0[..shorter]
+                {
+                    errors.push(format!(
+                        "`{}` for {} conflicts with `{}` for {} in the {} context",
+                        chord,
+                        action.name(),
+                        other_chord,
+                        other_action.name(),
+                        context.max(other_context).name()
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(KeyBindings { bindings })
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// Matches `keys` against the bindings of `context` and the global ones.
+    pub(crate) fn resolve(&self, context: KeyContext, keys: &[KeyPress]) -> KeyMatch {
+        let mut pending = false;
+        for (_, chord, action) in self.in_context(context) {
+            if chord.0 == keys {
+                return KeyMatch::Action(*action);
+            }
+            if chord.0.starts_with(keys) {
+                pending = true;
+            }
+        }
+        if pending {
+            KeyMatch::Pending
+        } else {
+            KeyMatch::None
+        }
+    }
+
+    fn in_context(
+        &self,
+        context: KeyContext,
+    ) -> impl Iterator<Item = &(KeyContext, KeyChord, ActionKind)> {
+        self.bindings
+            .iter()
+            .filter(move |(bound, _, _)| *bound == context || *bound == KeyContext::Global)
+    }
+
+    /// The keys of `action` that work in `context` for display, e.g. `a, Ctrl-n`.
+    pub(crate) fn keys_text_in(&self, context: KeyContext, action: ActionKind) -> String {
+        self.in_context(context)
+            .filter(|(_, _, bound)| *bound == action)
+            .map(|(_, chord, _)| chord.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Every action with at least one key that works in `context`.
+    pub(crate) fn actions_in(&self, context: KeyContext) -> Vec<ActionKind> {
+        ACTION_KINDS
+            .iter()
+            .copied()
+            .filter(|action| {
+                self.in_context(context)
+                    .any(|(_, _, bound)| bound == action)
+            })
+            .collect()
+    }
 }
 
 pub const DELETE_CHAR_KEYCODE: KeyCode = KeyCode::Backspace;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyPress {
+        KeyPress { code, modifiers }
+    }
+
+    fn bindings(config: &str) -> Result<KeyBindings, String> {
+        KeyBindings::new(&toml::from_str(config).unwrap())
+    }
+
+    #[test]
+    fn key_presses() {
+        let parse = |s: &str| KeyPress::from_str(s).unwrap();
+        assert_eq!(parse("a"), key(KeyCode::Char('a'), KeyModifiers::NONE));
+        assert_eq!(
+            parse("ctrl-s"),
+            key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse("Ctrl-Alt-x"),
+            key(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        );
+        // shift is folded into the key, the way crossterm reports it
+        assert_eq!(
+            parse("shift-d"),
+            key(KeyCode::Char('D'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse("shift-tab"),
+            key(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(parse("shift-tab"), parse("backtab"));
+        assert_eq!(parse("shift-up"), key(KeyCode::Up, KeyModifiers::SHIFT));
+        assert_eq!(parse("space"), key(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(parse("F12"), key(KeyCode::F(12), KeyModifiers::NONE));
+        assert!(KeyPress::from_str("f13").is_err());
+        assert!(KeyPress::from_str("ctrl-").is_err());
+        assert!(KeyPress::from_str("hyper-a").is_err());
+    }
+
+    #[test]
+    fn chords() {
+        let chord = KeyChord::from_str("g  g").unwrap();
+        assert_eq!(chord.0, [key(KeyCode::Char('g'), KeyModifiers::NONE); 2]);
+        assert_eq!(chord.to_string(), "g g");
+        assert!(KeyChord::from_str(" ").is_err());
+        assert!(KeyChord::from_str("g nope").is_err());
+    }
+
+    #[test]
+    fn default_bindings_are_valid() {
+        assert!(KeyBindings::new(&BTreeMap::new()).is_ok());
+    }
+
+    #[test]
+    fn prefix_conflicts() {
+        // `p l` shows the list pane, so a plain `p` could never wait for the `l`
+        let error = bindings("quit = \"p\"").err().unwrap();
+        assert!(
+            error.contains("`p` for quit conflicts with `p l` for toggle_list"),
+            "{}",
+            error
+        );
+        assert!(bindings("quit = \"ctrl-q\"").is_ok());
+    }
+
+    #[test]
+    fn global_bindings_on_plain_characters() {
+        let error = bindings("command_palette = \"x\"").err().unwrap();
+        assert!(
+            error.contains("would get in the way of typing"),
+            "{}",
+            error
+        );
+        let error = bindings("command_palette = \"shift-x\"").err().unwrap();
+        assert!(
+            error.contains("would get in the way of typing"),
+            "{}",
+            error
+        );
+        assert!(bindings("command_palette = \"ctrl-x\"").is_ok());
+        assert!(bindings("command_palette = \"f2\"").is_ok());
+    }
+
+    #[test]
+    fn config_errors_are_collected() {
+        let error = bindings("quit = \"nope-key\"\nfly = \"f\"").err().unwrap();
+        assert_eq!(error.lines().count(), 2, "{}", error);
+    }
+
+    #[test]
+    fn chords_wait_for_their_second_key() {
+        let bindings = KeyBindings::new(&BTreeMap::new()).unwrap();
+        let p = key(KeyCode::Char('p'), KeyModifiers::NONE);
+        let l = key(KeyCode::Char('l'), KeyModifiers::NONE);
+        assert!(bindings.resolve(KeyContext::Main, &[p]) == KeyMatch::Pending);
+        assert!(
+            bindings.resolve(KeyContext::Main, &[p, l])
+                == KeyMatch::Action(ActionKind::ToggleListPane)
+        );
+        assert!(bindings.resolve(KeyContext::Editing, &[p]) == KeyMatch::None);
+    }
+}
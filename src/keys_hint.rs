@@ -1,60 +1,204 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Rect},
-    widgets::{Block, Cell, Row, Table, Widget},
+    layout::{Constraint, Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{
+        Block, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        StatefulWidget, Table, Widget,
+    },
 };
 
 use crate::{
-    app::CurrentScreen,
-    input_keys::{ActionKind, ACTION_KINDS},
+    input_keys::{ActionKind, KeyBindings, KeyContext},
+    theme::Theme,
 };
 
-pub struct KeysHint {
-    pub _screen: CurrentScreen,
+/// Help popup listing the live key bindings that work in `context`.
+pub struct KeysHint<'a> {
+    pub context: KeyContext,
+    pub theme: &'a Theme,
+    pub key_bindings: &'a KeyBindings,
 }
 
-const KEY_CODE_MAX_NAME_LEN: u16 = length_of_longest_input_key_name(&ACTION_KINDS);
-const KEY_CODE_MAX_DESC_LEN: u16 = length_of_longest_input_key_description(&ACTION_KINDS);
+/// The search query and scroll position of the help popup.
+#[derive(Default)]
+pub struct KeysHintState {
+    pub query: String,
+    /// Rows scrolled down, clamped when rendered.
+    pub scroll: usize,
+}
+
+impl StatefulWidget for KeysHint<'_> {
+    type State = KeysHintState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut KeysHintState) {
+        let search = if state.query.is_empty() {
+            Line::from(" type to search ").italic()
+        } else {
+            Line::from(format!(" search: {} ", state.query))
+        };
+        let b = Block::bordered()
+            .border_style(self.theme.border)
+            .title(format!("Help ({})", self.context.name()))
+            .title_bottom(search);
 
-const fn length_of_longest_input_key_name(actions: &[ActionKind]) -> u16 {
-    let mut max_len = actions[0].input_key().name.len();
-    let mut i = 0;
-    while i < actions.len() {
-        let new_len = actions[i].input_key().name.len();
-        if new_len > max_len {
-            max_len = new_len;
+        let query = state.query.to_lowercase();
+        let mut actions: Vec<ActionKind> = self
+            .key_bindings
+            .actions_in(self.context)
+            .into_iter()
+            .filter(|action| {
+                query.is_empty()
+                    || action.description().to_lowercase().contains(&query)
+                    || action.name().contains(&query)
+                    || self
+                        .key_bindings
+                        .keys_text_in(self.context, *action)
+                        .to_lowercase()
+                        .contains(&query)
+            })
+            .collect();
+        actions.sort_by_key(|action| action.category());
+
+        let mut entries: Vec<(String, String)> = Vec::new();
+        let mut category = None;
+        for action in actions {
+            if category != Some(action.category()) {
+                category = Some(action.category());
+                entries.push((String::new(), action.category().to_text().to_string()));
+            }
+            entries.push((
+                self.key_bindings.keys_text_in(self.context, action),
+                action.description().to_string(),
+            ));
+        }
+
+        let inner = b.inner(area);
+        let max_scroll = entries.len().saturating_sub(inner.height as usize);
+        state.scroll = state.scroll.min(max_scroll);
+
+        let key_width = entries
+            .iter()
+            .map(|(keys, _)| keys.len())
+            .max()
+            .unwrap_or(0);
+        let rows = entries
+            .iter()
+            .skip(state.scroll)
+            .map(|(keys, description)| {
+                if keys.is_empty() {
+                    Row::new(vec![Cell::from(description.as_str())]).style(Style::new().bold())
+                } else {
+                    Row::new(vec![
+                        Cell::from(format!("  {}", keys)),
+                        Cell::from(description.as_str()),
+                    ])
+                }
+            });
+        let widths = [
+            Constraint::Length(key_width as u16 + 2),
+            Constraint::Fill(1),
+        ];
+        Widget::render(Table::new(rows, widths).column_spacing(2), inner, buf);
+        b.render(area, buf);
+
+        if max_scroll > 0 {
+            let mut scrollbar_state = ScrollbarState::new(max_scroll).position(state.scroll);
+            Scrollbar::new(ScrollbarOrientation::VerticalRight).render(
+                area.inner(Margin::new(0, 1)),
+                buf,
+                &mut scrollbar_state,
+            );
         }
-        i += 1;
     }
-    max_len as u16
 }
 
-const fn length_of_longest_input_key_description(actions: &[ActionKind]) -> u16 {
-    let mut max_len = actions[0].input_key().description.len();
-    let mut i = 0;
-    while i < actions.len() {
-        let new_len = actions[i].input_key().description.len();
-        if new_len > max_len {
-            max_len = new_len;
-        }
-        i += 1;
+/// Most used actions of each context with a short label for the hint bar.
+fn hint_actions(context: KeyContext) -> &'static [(ActionKind, &'static str)] {
+    match context {
+        KeyContext::Main => &[
+            (ActionKind::AddTask, "add"),
+            (ActionKind::EditMode, "edit"),
+            (ActionKind::MarkTaskDone, "done"),
+            (ActionKind::ShuffleTasks, "next"),
+            (ActionKind::ToggleFocusTimer, "focus"),
+            (ActionKind::CommandPalette, "commands"),
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::Quit, "quit"),
+        ],
+        KeyContext::Editing => &[
+            (ActionKind::FocusTitle, "title"),
+            (ActionKind::FocusDescription, "description"),
+            (ActionKind::SaveTask, "save"),
+            (ActionKind::OpenEditor, "$EDITOR"),
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::ChangeMode, "back"),
+        ],
+        KeyContext::Calendar => &[
+            (ActionKind::MoveRight, "day"),
+            (ActionKind::MoveDown, "week"),
+            (ActionKind::NextPage, "month"),
+            (ActionKind::GoToToday, "today"),
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::ChangeMode, "back"),
+        ],
+        KeyContext::Agenda => &[
+            (ActionKind::MarkTaskDone, "done"),
+            (ActionKind::IncrementDueDate, "later"),
+            (ActionKind::DecrementDueDate, "earlier"),
+            (ActionKind::EditMode, "edit"),
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::ChangeMode, "back"),
+        ],
+        KeyContext::Planner => &[
+            (ActionKind::MoveTaskRight, "reschedule"),
+            (ActionKind::MoveTaskDown, "reorder"),
+            (ActionKind::NextPage, "week"),
+            (ActionKind::MarkTaskDone, "done"),
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::ChangeMode, "back"),
+        ],
+        KeyContext::Board => &[
+            (ActionKind::MoveTaskRight, "move card"),
+            (ActionKind::MoveRight, "column"),
+            (ActionKind::MarkTaskDone, "done"),
+            (ActionKind::EditMode, "edit"),
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::ChangeMode, "back"),
+        ],
+        KeyContext::Dashboard => &[
+            (ActionKind::KeysHint, "help"),
+            (ActionKind::ChangeMode, "back"),
+        ],
+        KeyContext::TextField | KeyContext::Global => &[
+            (ActionKind::ChangeMode, "leave field"),
+            (ActionKind::SaveTask, "save"),
+        ],
     }
-    max_len as u16
 }
-impl Widget for KeysHint {
+
+/// One line at the bottom of the screen with the keys most likely needed next.
+pub struct HintBar<'a> {
+    pub context: KeyContext,
+    pub key_bindings: &'a KeyBindings,
+}
+
+impl Widget for HintBar<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let b = Block::bordered().title("Help");
-        let rows = ACTION_KINDS.map(|action| {
-            let key = action.input_key();
-            let cells = vec![Cell::from(key.name), Cell::from(key.description)];
-            Row::new(cells)
-        });
-        let widths = [
-            Constraint::Length(KEY_CODE_MAX_NAME_LEN),
-            Constraint::Length(KEY_CODE_MAX_DESC_LEN),
-        ];
-        let table = Table::new(rows, widths);
-        table.render(b.inner(area), buf);
-        b.render(area, buf);
+        let mut spans = Vec::new();
+        for (action, label) in hint_actions(self.context) {
+            // show only the first key, the help popup lists the rest
+            let keys = self.key_bindings.keys_text_in(self.context, *action);
+            let Some(key) = keys.split(", ").next().filter(|key| !key.is_empty()) else {
+                continue;
+            };
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::raw(key.to_string()).bold());
+            spans.push(Span::raw(format!(" {}", label)).dim());
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
     }
 }
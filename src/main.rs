@@ -1,18 +1,49 @@
+mod agenda;
 mod app;
+mod board;
+mod calendar;
+mod command_line;
+mod command_palette;
+mod config;
+mod daemon;
+mod dates;
+mod export;
+mod external_editor;
 mod input_keys;
 mod keys_hint;
+mod markdown;
+mod panes;
+mod planner;
+mod pomodoro;
+mod reminders;
+mod stats;
 mod task;
+mod text_area;
+mod theme;
+mod tracking;
 mod ui;
+mod vim;
 use crate::app::App;
 use crate::ui::ui;
 use std::{
+    env,
     fs::File,
     io::{self, stdout, Read, Write},
+    ops::ControlFlow,
+    process,
+    time::Duration,
 };
 
-use app::{CurrentScreen, EditMode, Popup};
-use crossterm::event::KeyCode;
-use input_keys::{keycode_to_actionkind, ActionKind, DELETE_CHAR_KEYCODE};
+use app::{CurrentScreen, EditMode, Popup, TaskCreationMode};
+use command_line::{Command, CommandLineOutcome, CommandLineState};
+use command_palette::CommandPaletteState;
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, KeyCode,
+    KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use input_keys::{ActionKind, KeyBindings, KeyContext};
+use keys_hint::KeysHintState;
+use panes::{Pane, PaneLayout};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     crossterm::{
@@ -20,13 +51,30 @@ use ratatui::{
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         ExecutableCommand,
     },
+    layout::Position,
     Terminal,
 };
+use reminders::Reminders;
 use task::TaskStatus;
+use theme::Theme;
+use time::OffsetDateTime;
+use tracking::TimeReport;
+use vim::VimOutcome;
+
+/// Where the tasks are saved, next to the config.
+const DATA_PATH: &str = "task_data.json";
 
 fn main() -> io::Result<()> {
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
+    // reminders without the TUI, e.g. started from a login script
+    if env::args().nth(1).as_deref() == Some("--daemon") {
+        if let Err(e) = daemon::run() {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    init_terminal()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut app = if let Ok(app) = load_from_disk() {
@@ -34,145 +82,574 @@ fn main() -> io::Result<()> {
     } else {
         App::new()
     };
+    match config::load_config() {
+        Ok(config) => app.config = config,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => app.show_error(format!("{}: {}", config::CONFIG_PATH, e)),
+    }
+    match KeyBindings::new(&app.config.keys) {
+        Ok(key_bindings) => app.key_bindings = key_bindings,
+        Err(e) => app.show_error(format!(
+            "Invalid key bindings in {}, using the defaults:\n{}",
+            config::CONFIG_PATH,
+            e
+        )),
+    }
+    let (theme, errors) = Theme::new(&app.config.theme);
+    app.theme = theme;
+    if let Some(e) = errors {
+        app.show_error(format!("Invalid theme in {}:\n{}", config::CONFIG_PATH, e));
+    }
+    app.reminders = Reminders::new(&app.config.reminders);
+    match PaneLayout::new(&app.config.layout) {
+        Ok(panes) => app.panes = panes,
+        Err(e) => app.show_error(format!(
+            "Invalid layout in {}, using the default:\n{}",
+            config::CONFIG_PATH,
+            e
+        )),
+    }
 
     let _res = run_app(&mut terminal, &mut app);
 
     save_to_disk(&app)?;
 
+    restore_terminal()?;
+    Ok(())
+}
+
+fn init_terminal() -> io::Result<()> {
+    enable_raw_mode()?;
+    stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableBracketedPaste)?;
+    stdout().execute(EnableMouseCapture)?;
+    Ok(())
+}
+
+fn restore_terminal() -> io::Result<()> {
+    stdout().execute(DisableMouseCapture)?;
+    stdout().execute(DisableBracketedPaste)?;
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
 
+/// Hands the terminal to `$EDITOR` to edit the description, then takes it back.
+fn edit_description_externally<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+) -> io::Result<()> {
+    restore_terminal()?;
+    let edited = external_editor::edit_in_external_editor(&app.description_input.text());
+    init_terminal()?;
+    terminal.clear()?;
+
+    match edited {
+        Ok(description) => app.description_input.set_text(&description),
+        Err(e) => app.show_error(e.to_string()),
+    }
+    Ok(())
+}
+
 fn save_to_disk(app: &App) -> std::io::Result<()> {
-    let mut f = File::create("task_data.json")?;
+    let mut f = File::create(DATA_PATH)?;
     let json_string = serde_json::to_string(app)?;
     f.write_all(json_string.as_bytes())?;
     Ok(())
 }
 
 fn load_from_disk() -> std::io::Result<App> {
-    let mut file = File::open("task_data.json")?;
+    let mut file = File::open(DATA_PATH)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
     let app: App = serde_json::from_str(&contents)?;
     Ok(app)
 }
 
+/// How often the screen is redrawn without input, so the focus timer moves.
+const TICK_RATE: Duration = Duration::from_millis(250);
+
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
     loop {
         terminal.draw(|f| ui(f, app))?;
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
-                // Skip events that are not KeyEventKind::Press
-                continue;
-            }
-            match app.current_screen {
-                CurrentScreen::Main => match keycode_to_actionkind(key.code) {
-                    Some(ActionKind::AddTask(_)) => {
-                        app.edit_mode = Some(EditMode::Title);
-                        app.title_input = String::new();
-                        app.description_input = String::new();
-                        app.current_screen = CurrentScreen::Editing;
-                        app.edit_mode = Some(EditMode::Main);
-                    }
-                    Some(ActionKind::EditMode(_)) => {
-                        app.current_screen = CurrentScreen::Editing;
-                        app.edit_mode = Some(EditMode::Main);
-                        if let Some(task) = &app.current_task {
-                            app.edit_mode = Some(EditMode::Main);
-                            app.title_input = task.title.clone();
-                            if let Some(description) = &task.description {
-                                app.description_input = description.clone();
-                            }
-                        }
-                    }
-                    Some(ActionKind::Quit(_)) => {
-                        return Ok(());
-                    }
-                    Some(ActionKind::MarkTaskDone(_)) => {
-                        app.change_task_status(TaskStatus::Finished);
-                    }
-                    Some(ActionKind::MarkTaskInProgress(_)) => {
-                        app.change_task_status(TaskStatus::InProgress);
-                    }
-                    Some(ActionKind::ShuffleTasks(_)) => {
-                        app.choose_shown_task();
-                    }
-                    Some(ActionKind::KeysHint(_)) => {
-                        app.popup = Some(app::Popup::Help);
-                    }
-                    Some(ActionKind::ChangeMode(_)) => {
-                        app.popup = None;
-                    }
-                    _ => {}
-                },
-                CurrentScreen::Editing => {
-                    let maybe_action = keycode_to_actionkind(key.code);
-                    match (app.edit_mode, maybe_action) {
-                        (Some(EditMode::Main), Some(action)) => {
-                            main_edit_mode_action_mapping(action, app);
-                        }
-                        (Some(_), Some(ActionKind::ChangeMode(_))) => match app.popup {
-                            Some(_) => app.popup = None,
-                            None => app.edit_mode = Some(EditMode::Main),
-                        },
-                        (Some(EditMode::Title), _) => {
-                            type_to_string(key.code, &mut app.title_input);
-                        }
-                        (Some(EditMode::Description), _) => {
-                            type_to_string(key.code, &mut app.description_input);
-                        }
-                        _ => {}
+        app.show_started_task();
+        let reminded = app.check_reminders() && app.config.reminders.bell;
+        if app.tick() || reminded {
+            ring_bell()?;
+        }
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
+        let flow = match event::read()? {
+            Event::Paste(text) => {
+                paste_into_field(&text, app);
+                ControlFlow::Continue(())
+            }
+            // Skip events that are not KeyEventKind::Press
+            Event::Key(key) if key.kind != event::KeyEventKind::Release => {
+                handle_key(terminal, app, key)?
+            }
+            Event::Mouse(mouse) => handle_mouse(terminal, app, mouse)?,
+            _ => ControlFlow::Continue(()),
+        };
+        if flow.is_break() {
+            return Ok(());
+        }
+    }
+}
+
+/// Rings the terminal bell, e.g. when a focus period ends.
+fn ring_bell() -> io::Result<()> {
+    let mut out = stdout();
+    out.write_all(b"\x07")?;
+    out.flush()
+}
+
+fn handle_key<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    key: KeyEvent,
+) -> io::Result<ControlFlow<()>> {
+    if let Some(Popup::Help) = app.popup {
+        // the screen behind only gets the keys that close the help
+        if !help_input(key, app) && app.action_for_key(key) == Some(ActionKind::ChangeMode) {
+            app.keys_hint = KeysHintState::default();
+            app.popup = None;
+        }
+        return Ok(ControlFlow::Continue(()));
+    }
+    if let Some(Popup::CommandLine) = app.popup {
+        if let Some(flow) = command_line_input(terminal, app, key)? {
+            return Ok(flow);
+        }
+    }
+    if let Some(Popup::CommandPalette) = app.popup {
+        match command_palette_input(key, app) {
+            Some(Some(action)) => return perform_action(terminal, app, action),
+            Some(None) => return Ok(ControlFlow::Continue(())),
+            None => {}
+        }
+    }
+
+    let context = app.key_context();
+    match (context, app.action_for_key(key)) {
+        // Esc closes a popup first, even one shown over a field
+        (_, Some(ActionKind::ChangeMode)) if app.popup.is_some() => {
+            return perform_action(terminal, app, ActionKind::ChangeMode);
+        }
+        // vim handles Esc itself, to leave insert mode before leaving the field
+        (KeyContext::TextField, Some(ActionKind::ChangeMode)) if !app.config.editor.vim_mode => {
+            app.edit_mode = Some(EditMode::Main);
+        }
+        (KeyContext::TextField, None | Some(ActionKind::ChangeMode)) => {
+            // keys a popup ignored must not type into the field behind it
+            if app.pending_keys.is_empty() && app.popup.is_none() {
+                type_into_field(key, app);
+            }
+        }
+        (_, Some(action)) => return perform_action(terminal, app, action),
+        (_, None) => {}
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Clicks select and focus what is under the mouse, the wheel scrolls it.
+fn handle_mouse<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    mouse: MouseEvent,
+) -> io::Result<ControlFlow<()>> {
+    if app.popup.is_some() {
+        return Ok(ControlFlow::Continue(()));
+    }
+    let position = Position::new(mouse.column, mouse.row);
+    let areas = &app.click_areas;
+    let scroll = match mouse.kind {
+        MouseEventKind::ScrollDown => 1,
+        MouseEventKind::ScrollUp => -1,
+        // a task pressed in the planner is rescheduled to the day it is let go on
+        MouseEventKind::Up(MouseButton::Left) if app.planner.dragging => {
+            app.planner.dragging = false;
+            if let Some((day, _)) = areas.planner_day_at(position) {
+                let days = (day - app.planner.day()).whole_days();
+                if days != 0 {
+                    app.move_planned_task(days);
+                }
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some((day, row)) = areas.planner_day_at(position) {
+                let offset = if day == app.planner.day() {
+                    app.planner.list.offset()
+                } else {
+                    0
+                };
+                let row = row as usize + offset;
+                // only a press on a task starts dragging it
+                app.planner.dragging = row < planner::day_rows(app.all_tasks(), day).len();
+                app.planner.select(day, row);
+            } else if areas.task_list.contains(position) {
+                let row = (position.y - areas.task_list.y) as usize + app.task_list.offset();
+                if let Some(index) = app.listed_tasks().get(row) {
+                    app.show_task(*index);
+                }
+            } else if areas.title_field.contains(position) {
+                return perform_action(terminal, app, ActionKind::FocusTitle);
+            } else if areas.description_field.contains(position) {
+                return perform_action(terminal, app, ActionKind::FocusDescription);
+            } else if let Some(date) = areas.calendar_date_at(position) {
+                match app.current_screen {
+                    CurrentScreen::Editing => app.set_edited_task_due_date(date),
+                    // the calendar pane opens the calendar screen on the day
+                    _ => {
+                        app.calendar.select(date);
+                        app.current_screen = CurrentScreen::Calendar;
                     }
                 }
             }
+            return Ok(ControlFlow::Continue(()));
+        }
+        _ => return Ok(ControlFlow::Continue(())),
+    };
+
+    if areas.task_list.contains(position) {
+        app.scroll_task_list(scroll);
+    } else if areas.description_field.contains(position) {
+        if scroll > 0 {
+            app.description_input.move_down();
+        } else {
+            app.description_input.move_up();
+        }
+    } else if areas.task_view.contains(position) {
+        app.scroll_description(scroll as i16);
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Searching and scrolling in the help popup, returns `false` for keys it ignores.
+fn help_input(key: KeyEvent, app: &mut App) -> bool {
+    let state = &mut app.keys_hint;
+    match key.code {
+        KeyCode::Esc => {
+            *state = KeysHintState::default();
+            app.popup = None;
+        }
+        KeyCode::Up => state.scroll = state.scroll.saturating_sub(1),
+        KeyCode::Down => state.scroll += 1,
+        KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(10),
+        KeyCode::PageDown => state.scroll += 10,
+        KeyCode::Backspace => {
+            state.query.pop();
+        }
+        KeyCode::Char(ch)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            state.query.push(ch);
+            state.scroll = 0;
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Filtering and choosing in the command palette.
+///
+/// Returns the chosen action once Enter is pressed, or `None` for keys it ignores.
+fn command_palette_input(key: KeyEvent, app: &mut App) -> Option<Option<ActionKind>> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let context = app.key_context();
+    let state = &mut app.command_palette;
+    match key.code {
+        KeyCode::Esc => {
+            *state = CommandPaletteState::default();
+            app.popup = None;
+        }
+        KeyCode::Enter => {
+            let action = state.selected_action(context);
+            *state = CommandPaletteState::default();
+            app.popup = None;
+            return Some(action);
+        }
+        KeyCode::Up | KeyCode::BackTab => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Char('k') if ctrl => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Tab => state.selected += 1,
+        KeyCode::Char('j') if ctrl => state.selected += 1,
+        KeyCode::Backspace => {
+            state.query.pop();
+            state.selected = 0;
+        }
+        KeyCode::Char(ch) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+            state.query.push(ch);
+            state.selected = 0;
+        }
+        _ => return None,
+    }
+    Some(None)
+}
+
+/// Typing into the command line, returns `None` for keys it ignores.
+fn command_line_input<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    key: KeyEvent,
+) -> io::Result<Option<ControlFlow<()>>> {
+    let tags = app.tags();
+    match app.command_line.input(key, &app.command_history, &tags) {
+        CommandLineOutcome::Handled => {}
+        CommandLineOutcome::Ignored => return Ok(None),
+        CommandLineOutcome::Cancel => {
+            app.command_line = CommandLineState::default();
+            app.popup = None;
+        }
+        CommandLineOutcome::Run(line) => {
+            app.command_line = CommandLineState::default();
+            app.popup = None;
+            command_line::push_history(&mut app.command_history, &line);
+            return run_command(terminal, app, &line).map(Some);
+        }
+    }
+    Ok(Some(ControlFlow::Continue(())))
+}
+
+/// Runs a line from the command line through the same code as the key bindings.
+fn run_command<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    line: &str,
+) -> io::Result<ControlFlow<()>> {
+    let command = match line.parse::<Command>() {
+        Ok(command) => command,
+        Err(e) => {
+            app.show_error(e);
+            return Ok(ControlFlow::Continue(()));
+        }
+    };
+    match command {
+        Command::Add(title) => app.add_task(task::Task::default(title)),
+        Command::Due(days) => app.change_active_task_due_date(days),
+        Command::Tag(tag) => app.tag_active_task(&tag),
+        Command::Untag(tag) => app.untag_active_task(&tag),
+        Command::Done => return perform_action(terminal, app, ActionKind::MarkTaskDone),
+        Command::Sort(key) => app.sort_tasks(key),
+        Command::Write => return perform_action(terminal, app, ActionKind::SaveTask),
+        Command::Quit => return perform_action(terminal, app, ActionKind::Quit),
+        // stay open if saving fails, so the error can be read
+        Command::WriteQuit => match save_to_disk(app) {
+            Ok(()) => return Ok(ControlFlow::Break(())),
+            Err(e) => app.show_error(format!("Could not save tasks: {}", e)),
+        },
+        Command::ExportCsv(path) => {
+            if let Err(e) = export::export_csv(app.all_tasks(), &path) {
+                app.show_error(format!("Could not export to {}: {}", path.display(), e));
+            }
+        }
+        Command::Estimate(estimate) => app.set_active_task_estimate(estimate),
+        Command::Snooze(duration) => app.snooze_active_task(duration),
+        Command::Remind(Some(before)) => app.add_active_task_reminder(before),
+        Command::Remind(None) => app.clear_active_task_reminders(),
+        Command::Start(date) => {
+            let offset = OffsetDateTime::now_local()
+                .unwrap_or_else(|_| OffsetDateTime::now_utc())
+                .offset();
+            app.set_active_task_start(date.map(|date| date.midnight().assume_offset(offset)));
+        }
+        Command::Report(from, to) => {
+            let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+            let to = to.unwrap_or(now.date());
+            let from = from.unwrap_or_else(|| to.saturating_sub(time::Duration::days(6)));
+            app.time_report = Some(TimeReport::new(app.all_tasks(), from, to, now));
+            app.popup = Some(Popup::TimeReport);
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+fn type_into_field(key: KeyEvent, app: &mut App) {
+    let field = match app.edit_mode {
+        Some(field @ (EditMode::Title | EditMode::Description)) => field,
+        _ => return,
+    };
+    let multiline = field == EditMode::Description;
+    let text_area = if multiline {
+        &mut app.description_input
+    } else {
+        &mut app.title_input
+    };
+
+    if app.config.editor.vim_mode {
+        if app.vim.input(key, text_area, multiline) == VimOutcome::LeaveField {
+            app.edit_mode = Some(EditMode::Main);
+        }
+    } else if key.code == KeyCode::Enter {
+        if multiline {
+            text_area.insert_newline();
         }
+    } else {
+        text_area.input(key);
     }
 }
 
-fn type_to_string(key: KeyCode, field: &mut String) {
-    match key {
-        DELETE_CHAR_KEYCODE => {
-            field.pop();
+fn paste_into_field(text: &str, app: &mut App) {
+    match (app.popup, app.current_screen, app.edit_mode) {
+        (Some(Popup::CommandLine), _, _) => app.command_line.input.insert_str(text, false),
+        // like keys, a paste must not reach a field behind a popup
+        (Some(_), _, _) => {}
+        (None, CurrentScreen::Editing, Some(EditMode::Title)) => {
+            app.title_input.insert_str(text, false)
         }
-        KeyCode::Char(ch) => {
-            field.push(ch);
+        (None, CurrentScreen::Editing, Some(EditMode::Description)) => {
+            app.description_input.insert_str(text, true)
         }
         _ => {}
     }
 }
 
-fn main_edit_mode_action_mapping(action: ActionKind, app: &mut App) {
+/// Runs an action the key bindings resolved to, `Break` means quit.
+fn perform_action<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    action: ActionKind,
+) -> io::Result<ControlFlow<()>> {
     match action {
-        ActionKind::ChangeMode(_) => match app.popup {
-            Some(_) => {
+        ActionKind::Quit => return Ok(ControlFlow::Break(())),
+        ActionKind::ChangeMode => match (app.popup, app.current_screen) {
+            (Some(Popup::Reminder), _) => {
                 app.popup = None;
+                app.notices.clear();
             }
-            None => {
-                app.current_screen = CurrentScreen::Main;
+            (Some(_), _) => app.popup = None,
+            (None, CurrentScreen::Editing) if app.edit_mode != Some(EditMode::Main) => {
+                app.edit_mode = Some(EditMode::Main);
             }
+            (None, CurrentScreen::Main) => {}
+            (None, _) => app.current_screen = CurrentScreen::Main,
         },
-        ActionKind::AddTask(_) => {
-            app.save_task();
-            app.current_screen = CurrentScreen::Main;
+        ActionKind::KeysHint => app.popup = Some(Popup::Help),
+        ActionKind::CommandLine => {
+            app.command_line = CommandLineState::default();
+            app.popup = Some(Popup::CommandLine);
+        }
+        ActionKind::CommandPalette => {
+            app.command_palette = CommandPaletteState::default();
+            app.popup = Some(Popup::CommandPalette);
+        }
+        ActionKind::AddTask => app.start_editing(TaskCreationMode::CreateNew),
+        ActionKind::EditMode => {
+            if app.show_selected_task() {
+                app.start_editing(TaskCreationMode::Active);
+            }
+        }
+        ActionKind::SaveTask => {
+            if let CurrentScreen::Editing = app.current_screen {
+                app.save_task();
+                app.current_screen = CurrentScreen::Main;
+                app.edit_mode = None;
+            }
+            if let Err(e) = save_to_disk(app) {
+                app.show_error(format!("Could not save tasks: {}", e));
+            }
+        }
+        ActionKind::MarkTaskDone => {
+            if app.show_selected_task() {
+                app.change_task_status(TaskStatus::Finished);
+            }
         }
-        ActionKind::FocusTitle(_) => {
+        ActionKind::MarkTaskInProgress => app.change_task_status(TaskStatus::InProgress),
+        ActionKind::ShuffleTasks => app.choose_shown_task(),
+        ActionKind::ScrollDown => app.scroll_description(1),
+        ActionKind::ScrollUp => app.scroll_description(-1),
+        ActionKind::FocusTitle => {
             app.edit_mode = Some(EditMode::Title);
+            app.vim.reset();
         }
-        ActionKind::FocusDescription(_) => {
+        ActionKind::FocusDescription => {
             app.edit_mode = Some(EditMode::Description);
+            app.vim.reset();
+        }
+        ActionKind::IncrementDueDate => {
+            if app.show_selected_task() {
+                app.change_active_task_due_date(1);
+            }
         }
-        ActionKind::IncrementDueDate(_) => {
-            app.change_active_task_due_date(1);
+        ActionKind::DecrementDueDate => {
+            if app.show_selected_task() {
+                app.change_active_task_due_date(-1);
+            }
         }
-        ActionKind::DecrementDueDate(_) => {
-            app.change_active_task_due_date(1);
+        ActionKind::OpenEditor => edit_description_externally(terminal, app)?,
+        ActionKind::ToggleListPane => app.panes.toggle(Pane::List),
+        ActionKind::ToggleDetailPane => app.panes.toggle(Pane::Detail),
+        ActionKind::ToggleCalendarPane => app.panes.toggle(Pane::Calendar),
+        ActionKind::ToggleAgendaPane => app.panes.toggle(Pane::Agenda),
+        ActionKind::ToggleStatsPane => app.panes.toggle(Pane::Stats),
+        ActionKind::FocusNextPane => app.panes.focus_next(),
+        ActionKind::GrowPane => app.panes.resize_focused(1),
+        ActionKind::ShrinkPane => app.panes.resize_focused(-1),
+        ActionKind::OpenCalendar => app.current_screen = CurrentScreen::Calendar,
+        ActionKind::OpenAgenda => app.current_screen = CurrentScreen::Agenda,
+        ActionKind::OpenPlanner => app.current_screen = CurrentScreen::Planner,
+        ActionKind::OpenBoard => app.current_screen = CurrentScreen::Board,
+        ActionKind::OpenDashboard => app.current_screen = CurrentScreen::Dashboard,
+        ActionKind::ToggleFocusTimer => app.toggle_focus_timer(),
+        ActionKind::Snooze => {
+            if app.show_selected_task() {
+                let hours = app.config.shuffle.snooze_hours;
+                app.snooze_active_task(time::Duration::hours(hours));
+            }
         }
-        ActionKind::KeysHint(_) => {
-            app.popup = Some(Popup::Help);
+        ActionKind::ToggleTimeTracking => {
+            if app.show_selected_task() {
+                app.toggle_time_tracking();
+            }
         }
-        _ => {}
+        ActionKind::MoveLeft => match app.current_screen {
+            CurrentScreen::Planner => app.planner.move_days(-1),
+            CurrentScreen::Board => app.board.move_column(-1),
+            _ => app.calendar.move_days(-1),
+        },
+        ActionKind::MoveRight => match app.current_screen {
+            CurrentScreen::Planner => app.planner.move_days(1),
+            CurrentScreen::Board => app.board.move_column(1),
+            _ => app.calendar.move_days(1),
+        },
+        ActionKind::MoveUp => match app.current_screen {
+            CurrentScreen::Agenda => app.agenda.move_selection(-1),
+            CurrentScreen::Planner => app.planner.move_row(-1),
+            CurrentScreen::Board => app.board.move_row(-1),
+            _ => app.calendar.move_days(-7),
+        },
+        ActionKind::MoveDown => match app.current_screen {
+            CurrentScreen::Agenda => app.agenda.move_selection(1),
+            CurrentScreen::Planner => app.planner.move_row(1),
+            CurrentScreen::Board => app.board.move_row(1),
+            _ => app.calendar.move_days(7),
+        },
+        ActionKind::PreviousPage => match app.current_screen {
+            CurrentScreen::Planner => app.planner.move_days(-7),
+            _ => app.calendar.move_months(-1),
+        },
+        ActionKind::NextPage => match app.current_screen {
+            CurrentScreen::Planner => app.planner.move_days(7),
+            _ => app.calendar.move_months(1),
+        },
+        ActionKind::GoToToday => match app.current_screen {
+            CurrentScreen::Planner => app.planner.select_today(),
+            _ => app.calendar.select_today(),
+        },
+        ActionKind::MoveTaskLeft => match app.current_screen {
+            CurrentScreen::Board => app.move_board_card(-1),
+            _ => app.move_planned_task(-1),
+        },
+        ActionKind::MoveTaskRight => match app.current_screen {
+            CurrentScreen::Board => app.move_board_card(1),
+            _ => app.move_planned_task(1),
+        },
+        ActionKind::MoveTaskUp => app.reorder_planned_task(-1),
+        ActionKind::MoveTaskDown => app.reorder_planned_task(1),
     }
+    Ok(ControlFlow::Continue(()))
 }
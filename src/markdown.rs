@@ -0,0 +1,216 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+use crate::theme::Theme;
+
+/// Converts markdown into styled text for a `Paragraph`.
+///
+/// Only the parts of markdown that make sense in a terminal are styled,
+/// anything else (html, images, tables) is shown as its plain text.
+pub(crate) fn markdown_to_text(source: &str, theme: &Theme) -> Text<'static> {
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut renderer = MarkdownRenderer {
+        code: theme.code,
+        link: theme.link,
+        muted: theme.muted,
+        ..MarkdownRenderer::default()
+    };
+    for event in Parser::new_ext(source, options) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct MarkdownRenderer {
+    lines: Vec<Line<'static>>,
+    spans: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    /// The next number of each nested list, `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    link_url: Option<String>,
+    in_code_block: bool,
+    quote_depth: usize,
+    code: Style,
+    link: Style,
+    muted: Style,
+}
+
+impl MarkdownRenderer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => {
+                for line in text.lines() {
+                    self.spans
+                        .push(Span::styled(format!("  {}", line), self.code));
+                    self.flush_line();
+                }
+            }
+            Event::Text(text) => self.push_text(&text),
+            Event::Code(code) => self.spans.push(Span::styled(code.to_string(), self.code)),
+            Event::InlineHtml(html) | Event::Html(html) => self.push_text(&html),
+            Event::InlineMath(math) | Event::DisplayMath(math) => {
+                self.spans.push(Span::styled(math.to_string(), self.code))
+            }
+            Event::FootnoteReference(name) => self.push_text(&format!("[^{}]", name)),
+            Event::SoftBreak => self.push_text(" "),
+            Event::HardBreak => self.flush_line(),
+            Event::Rule => {
+                self.flush_line();
+                self.lines.push(Line::styled("────────", self.muted));
+                self.end_block();
+            }
+            Event::TaskListMarker(checked) => {
+                // replace the bullet that `Item` already pushed
+                self.spans.pop();
+                let marker = if checked { "[x] " } else { "[ ] " };
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                self.spans.push(Span::raw(format!("{}{}", indent, marker)));
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush_line();
+                let style = match level {
+                    HeadingLevel::H1 => {
+                        Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                    }
+                    HeadingLevel::H2 => Style::new().add_modifier(Modifier::BOLD),
+                    _ => Style::new().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+                };
+                self.styles.push(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.flush_line();
+                self.quote_depth += 1;
+                self.styles
+                    .push(Style::new().add_modifier(Modifier::ITALIC));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush_line();
+                self.in_code_block = true;
+                if let CodeBlockKind::Fenced(language) = kind {
+                    if !language.is_empty() {
+                        self.lines
+                            .push(Line::styled(format!("  {}", language), self.muted));
+                    }
+                }
+            }
+            Tag::List(start) => {
+                self.flush_line();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush_line();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let bullet = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}{}. ", indent, *number - 1)
+                    }
+                    _ => format!("{}• ", indent),
+                };
+                self.spans.push(Span::raw(bullet));
+            }
+            Tag::Emphasis => self.push_style(Style::new().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::new().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self.push_style(Style::new().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { dest_url, .. } => {
+                self.link_url = Some(dest_url.to_string());
+                self.push_style(self.link);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush_line();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush_line();
+                self.end_block();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_line();
+                self.styles.pop();
+                self.quote_depth -= 1;
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.end_block();
+            }
+            TagEnd::List(_) => {
+                self.flush_line();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            TagEnd::Item => self.flush_line(),
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                if let Some(url) = self.link_url.take() {
+                    let shown = self.spans.last().map(|span| span.content.as_ref());
+                    if shown != Some(url.as_str()) {
+                        self.spans
+                            .push(Span::styled(format!(" ({})", url), self.muted));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_style(&mut self, style: Style) {
+        let current = self.styles.last().copied().unwrap_or_default();
+        self.styles.push(current.patch(style));
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let style = self.styles.last().copied().unwrap_or_default();
+        self.spans.push(Span::styled(text.to_string(), style));
+    }
+
+    fn flush_line(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+        let mut spans = std::mem::take(&mut self.spans);
+        if self.quote_depth > 0 {
+            spans.insert(0, Span::styled("│ ".repeat(self.quote_depth), self.muted));
+        }
+        self.lines.push(Line::from(spans));
+    }
+
+    /// Separates block elements with an empty line.
+    fn end_block(&mut self) {
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn finish(mut self) -> Text<'static> {
+        self.flush_line();
+        while self.lines.last().is_some_and(|line| line.width() == 0) {
+            self.lines.pop();
+        }
+        Text::from(self.lines)
+    }
+}
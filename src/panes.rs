@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// The parts the main screen can be split into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pane {
+    List,
+    Detail,
+    Calendar,
+    Agenda,
+    Stats,
+}
+
+pub(crate) const PANES: [Pane; 5] = [
+    Pane::List,
+    Pane::Detail,
+    Pane::Calendar,
+    Pane::Agenda,
+    Pane::Stats,
+];
+
+impl Pane {
+    /// The name of the pane in the `[layout]` config section.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Pane::List => "list",
+            Pane::Detail => "detail",
+            Pane::Calendar => "calendar",
+            Pane::Agenda => "agenda",
+            Pane::Stats => "stats",
+        }
+    }
+}
+
+impl FromStr for Pane {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        PANES
+            .iter()
+            .copied()
+            .find(|pane| pane.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = PANES.iter().map(|pane| pane.name()).collect();
+                format!("unknown pane `{}`, try one of {}", name, names.join(", "))
+            })
+    }
+}
+
+/// The `[layout]` config section.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// Panes from left to right, or top to bottom on a narrow terminal.
+    pub panes: Vec<String>,
+    /// Relative size of each pane, all the same when left out.
+    pub sizes: Vec<u16>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            panes: vec!["detail".to_string(), "list".to_string()],
+            sizes: Vec::new(),
+        }
+    }
+}
+
+/// Size a pane gets when no size is configured, sizes are relative to each other.
+const DEFAULT_SIZE: u16 = 10;
+/// Sizes are capped, so adding them up can not overflow.
+const MAX_SIZE: u16 = 100;
+
+/// The panes shown on the main screen with their sizes, changed at runtime
+/// by the pane actions.
+pub struct PaneLayout {
+    panes: Vec<(Pane, u16)>,
+    /// Index of the pane the resize actions apply to.
+    focused: usize,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        PaneLayout::new(&LayoutConfig::default()).expect("the default layout is valid")
+    }
+}
+
+impl PaneLayout {
+    /// Builds the layout from the config.
+    pub(crate) fn new(config: &LayoutConfig) -> Result<PaneLayout, String> {
+        let mut errors = Vec::new();
+        if !config.sizes.is_empty() && config.sizes.len() != config.panes.len() {
+            errors.push(format!(
+                "{} sizes given for {} panes",
+                config.sizes.len(),
+                config.panes.len()
+            ));
+        }
+        if config.sizes.contains(&0) {
+            errors.push("pane sizes must be at least 1".to_string());
+        }
+
+        let mut panes: Vec<(Pane, u16)> = Vec::new();
+        for (i, name) in config.panes.iter().enumerate() {
+            match Pane::from_str(name) {
+                Ok(pane) if panes.iter().any(|(shown, _)| *shown == pane) => {
+                    errors.push(format!("pane `{}` is listed twice", name))
+                }
+                Ok(pane) => {
+                    let size = config.sizes.get(i).copied().unwrap_or(DEFAULT_SIZE);
+                    panes.push((pane, size.clamp(1, MAX_SIZE)));
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        if config.panes.is_empty() {
+            errors.push("at least one pane has to be shown".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(PaneLayout { panes, focused: 0 })
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// The shown panes in order with their relative sizes.
+    pub(crate) fn panes(&self) -> &[(Pane, u16)] {
+        &self.panes
+    }
+
+    pub(crate) fn focused(&self) -> Pane {
+        self.panes[self.focused].0
+    }
+
+    /// Hides a shown pane or adds it at the end, the last pane can not be hidden.
+    pub(crate) fn toggle(&mut self, pane: Pane) {
+        match self.panes.iter().position(|(shown, _)| *shown == pane) {
+            Some(_) if self.panes.len() == 1 => {}
+            Some(index) => {
+                self.panes.remove(index);
+                self.focused = self.focused.min(self.panes.len() - 1);
+            }
+            None => {
+                let size = self.total_size() / self.panes.len() as u32;
+                self.panes.push((pane, (size as u16).clamp(1, MAX_SIZE)));
+                self.focused = self.panes.len() - 1;
+            }
+        }
+    }
+
+    pub(crate) fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % self.panes.len();
+    }
+
+    /// Grows or shrinks the focused pane by a tenth of the whole screen per step.
+    pub(crate) fn resize_focused(&mut self, steps: i16) {
+        let step = (self.total_size() / 10).max(1) as i16;
+        let size = &mut self.panes[self.focused].1;
+        *size = size
+            .saturating_add_signed(steps.saturating_mul(step))
+            .clamp(1, MAX_SIZE);
+    }
+
+    fn total_size(&self) -> u32 {
+        self.panes.iter().map(|(_, size)| u32::from(*size)).sum()
+    }
+}
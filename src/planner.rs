@@ -0,0 +1,58 @@
+use ratatui::widgets::ListState;
+use time::{Date, Duration};
+
+use crate::{dates, task::Task};
+
+/// The selected day and task of the week planner.
+#[derive(Default)]
+pub struct PlannerState {
+    /// Today until another day is picked.
+    day: Option<Date>,
+    /// Index into the tasks of the selected day, clamped when used.
+    pub row: usize,
+    /// A task was pressed with the mouse and is being dragged to another day.
+    pub dragging: bool,
+    /// Scroll position of the selected day.
+    pub list: ListState,
+}
+
+impl PlannerState {
+    pub(crate) fn day(&self) -> Date {
+        self.day.unwrap_or_else(dates::today)
+    }
+
+    pub(crate) fn select(&mut self, day: Date, row: usize) {
+        self.day = Some(day);
+        self.row = row;
+    }
+
+    pub(crate) fn select_today(&mut self) {
+        self.day = None;
+        self.row = 0;
+    }
+
+    pub(crate) fn move_days(&mut self, days: i64) {
+        let day = self.day().saturating_add(Duration::days(days));
+        self.select(day, 0);
+    }
+
+    pub(crate) fn move_row(&mut self, rows: isize) {
+        self.row = self.row.saturating_add_signed(rows);
+    }
+
+    /// The Monday of the shown week.
+    pub(crate) fn week_start(&self) -> Date {
+        let day = self.day();
+        day - Duration::days(day.weekday().number_days_from_monday().into())
+    }
+}
+
+/// The tasks due on `day` in the order of their due times, as indices into `tasks`.
+pub(crate) fn day_rows<'a>(tasks: impl Iterator<Item = &'a Task>, day: Date) -> Vec<usize> {
+    let tasks: Vec<&Task> = tasks.collect();
+    let mut rows: Vec<usize> = (0..tasks.len())
+        .filter(|i| tasks[*i].due_time.date() == day)
+        .collect();
+    rows.sort_by_key(|i| tasks[*i].due_time);
+    rows
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::task::FocusSession;
+
+/// The `[pomodoro]` config section.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PomodoroConfig {
+    pub work_minutes: u64,
+    pub break_minutes: u64,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        PomodoroConfig {
+            work_minutes: 25,
+            break_minutes: 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Work,
+    Break,
+}
+
+impl Period {
+    pub(crate) const fn to_text(self) -> &'static str {
+        match self {
+            Period::Work => "Focus",
+            Period::Break => "Break",
+        }
+    }
+}
+
+/// A running work or break period.
+pub struct FocusTimer {
+    pub period: Period,
+    /// The id of the task the timer was started on.
+    task: u64,
+    started: Instant,
+    /// The wall clock time the period started, for the recorded session.
+    started_at: OffsetDateTime,
+    length: Duration,
+}
+
+impl FocusTimer {
+    pub(crate) fn start(period: Period, task: u64, config: &PomodoroConfig) -> FocusTimer {
+        let minutes = match period {
+            Period::Work => config.work_minutes,
+            Period::Break => config.break_minutes,
+        };
+        FocusTimer {
+            period,
+            task,
+            started: Instant::now(),
+            started_at: OffsetDateTime::now_utc(),
+            // a zero length period would end on every tick
+            length: Duration::from_secs(minutes.max(1) * 60),
+        }
+    }
+
+    pub(crate) fn remaining(&self) -> Duration {
+        self.length.saturating_sub(self.started.elapsed())
+    }
+
+    /// How much of the period has passed, from 0 to 1.
+    pub(crate) fn ratio(&self) -> f64 {
+        (self.started.elapsed().as_secs_f64() / self.length.as_secs_f64()).min(1.0)
+    }
+
+    /// The id of the task the time is worked on.
+    pub(crate) fn task(&self) -> u64 {
+        self.task
+    }
+
+    pub(crate) fn is_over(&self) -> bool {
+        self.started.elapsed() >= self.length
+    }
+
+    /// The time worked so far, `None` for breaks and anything under a minute.
+    pub(crate) fn session(&self) -> Option<FocusSession> {
+        let worked = self.started.elapsed().min(self.length);
+        (self.period == Period::Work && worked >= Duration::from_secs(60)).then(|| FocusSession {
+            start: self.started_at,
+            end: self.started_at + worked,
+        })
+    }
+}
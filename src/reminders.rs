@@ -0,0 +1,299 @@
+use std::{
+    io,
+    process::{Child, Command, Stdio},
+};
+
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::{dates::relative_due, task::Task};
+
+/// The `[reminders]` config section.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ReminderConfig {
+    /// Ring the terminal bell when a reminder goes off.
+    pub bell: bool,
+    /// Run with the task title and when it is due as two more arguments,
+    /// e.g. `notify-send`.
+    pub command: Option<String>,
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        ReminderConfig {
+            bell: true,
+            command: None,
+        }
+    }
+}
+
+/// Where the current time comes from, so reminders can be checked against any time.
+pub trait Clock {
+    fn now(&self) -> OffsetDateTime;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// Tells about a reminder outside of the terminal.
+pub trait Notifier {
+    fn notify(&mut self, notice: &Notice) -> io::Result<()>;
+
+    /// A failure of an earlier notice that only showed up since, for
+    /// notifiers that do not wait for the notice to be delivered.
+    fn finished(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs the configured command for every reminder.
+///
+/// The command is not waited for, a notifier that stays open until it is
+/// dismissed, e.g. `zenity --info`, must not freeze the screen.
+pub struct CommandNotifier {
+    command: String,
+    /// Commands started that have not exited yet.
+    running: Vec<Child>,
+}
+
+impl CommandNotifier {
+    pub(crate) fn new(command: &str) -> CommandNotifier {
+        CommandNotifier {
+            command: command.to_string(),
+            running: Vec::new(),
+        }
+    }
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&mut self, notice: &Notice) -> io::Result<()> {
+        // allow commands that need flags, e.g. `notify-send -u critical`
+        let mut args = self.command.split_whitespace();
+        let Some(program) = args.next() else {
+            return Ok(());
+        };
+        // keep the command from writing over the screen
+        let child = Command::new(program)
+            .args(args)
+            .arg(&notice.title)
+            .arg(&notice.body)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("could not start {}: {}", program, e)))?;
+        self.running.push(child);
+        Ok(())
+    }
+
+    fn finished(&mut self) -> io::Result<()> {
+        let program = self.command.split_whitespace().next().unwrap_or_default();
+        let mut result = Ok(());
+        self.running.retain_mut(|child| match child.try_wait() {
+            Ok(None) => true,
+            Ok(Some(status)) => {
+                if !status.success() {
+                    result = Err(io::Error::other(format!(
+                        "{} exited with {}",
+                        program, status
+                    )));
+                }
+                false
+            }
+            Err(e) => {
+                result = Err(e);
+                false
+            }
+        });
+        result
+    }
+}
+
+/// A reminder that went off.
+pub struct Notice {
+    pub title: String,
+    /// When the task is due, e.g. `due in 1 hour`.
+    pub body: String,
+}
+
+/// The clock reminders are checked against and where they are sent besides
+/// the screen.
+pub struct Reminders {
+    pub clock: Box<dyn Clock>,
+    pub notifier: Option<Box<dyn Notifier>>,
+}
+
+impl Default for Reminders {
+    fn default() -> Self {
+        Reminders {
+            clock: Box::new(SystemClock),
+            notifier: None,
+        }
+    }
+}
+
+impl Reminders {
+    pub(crate) fn new(config: &ReminderConfig) -> Reminders {
+        Reminders {
+            notifier: config
+                .command
+                .as_deref()
+                .map(|command| Box::new(CommandNotifier::new(command)) as Box<dyn Notifier>),
+            ..Reminders::default()
+        }
+    }
+}
+
+/// Marks the reminders of `tasks` that went off by `now`, one notice per task.
+pub(crate) fn take_due<'a>(
+    tasks: impl Iterator<Item = &'a mut Task>,
+    now: OffsetDateTime,
+) -> Vec<Notice> {
+    tasks
+        .filter_map(|task| {
+            task.take_due_reminders(now).then(|| Notice {
+                title: task.title.clone(),
+                body: relative_due(task.due_time, now, task.due_time < now),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use time::{macros::datetime, Duration};
+
+    use super::*;
+    use crate::app::{App, CurrentScreen, EditMode, Popup};
+
+    const NOW: OffsetDateTime = datetime!(2024-03-01 12:00 UTC);
+
+    struct FixedClock(OffsetDateTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> OffsetDateTime {
+            self.0
+        }
+    }
+
+    /// Keeps the titles of the notices it was given.
+    struct RecordingNotifier(Rc<RefCell<Vec<String>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&mut self, notice: &Notice) -> io::Result<()> {
+            self.0.borrow_mut().push(notice.title.clone());
+            Ok(())
+        }
+    }
+
+    fn task_due_in(title: &str, due_in: Duration, reminders: &[Duration]) -> Task {
+        let mut task = Task::default(title.to_string());
+        task.due_time = NOW + due_in;
+        for before in reminders {
+            task.add_reminder(*before);
+        }
+        task
+    }
+
+    #[test]
+    fn reminders_go_off_once_per_task() {
+        let mut tasks = [
+            // both reminders are due, but only one notice is sent
+            task_due_in(
+                "soon",
+                Duration::minutes(5),
+                &[Duration::HOUR, Duration::minutes(10)],
+            ),
+            task_due_in("later", Duration::hours(3), &[Duration::HOUR]),
+            task_due_in("quiet", Duration::minutes(5), &[]),
+        ];
+
+        let notices = take_due(tasks.iter_mut(), NOW);
+        let titles: Vec<&str> = notices.iter().map(|notice| notice.title.as_str()).collect();
+        assert_eq!(titles, ["soon"]);
+        assert_eq!(notices[0].body, "due in 5m");
+
+        assert!(take_due(tasks.iter_mut(), NOW).is_empty());
+    }
+
+    #[test]
+    fn moving_the_due_date_sets_reminders_again() {
+        let mut tasks = [task_due_in("soon", Duration::minutes(5), &[Duration::HOUR])];
+        assert_eq!(take_due(tasks.iter_mut(), NOW).len(), 1);
+
+        tasks[0].due_time += Duration::DAY;
+        assert!(take_due(tasks.iter_mut(), NOW).is_empty());
+        let later = NOW + Duration::DAY;
+        assert_eq!(take_due(tasks.iter_mut(), later).len(), 1);
+    }
+
+    #[test]
+    fn finished_and_deferred_tasks_stay_quiet() {
+        let mut finished = task_due_in("finished", Duration::minutes(5), &[Duration::HOUR]);
+        finished.task_status = crate::task::TaskStatus::Finished;
+        let mut deferred = task_due_in("deferred", Duration::minutes(5), &[Duration::HOUR]);
+        deferred.start_time = Some(NOW + Duration::minutes(1));
+        let mut tasks = [finished, deferred];
+
+        assert!(take_due(tasks.iter_mut(), NOW).is_empty());
+        let started = NOW + Duration::minutes(2);
+        assert_eq!(take_due(tasks.iter_mut(), started).len(), 1);
+    }
+
+    #[test]
+    fn check_reminders_shows_and_sends_notices() {
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::new();
+        app.reminders = Reminders {
+            clock: Box::new(FixedClock(NOW)),
+            notifier: Some(Box::new(RecordingNotifier(sent.clone()))),
+        };
+        app.current_task = Some(task_due_in(
+            "shown",
+            Duration::minutes(30),
+            &[Duration::HOUR],
+        ));
+        app.tasks = vec![
+            task_due_in("waiting", Duration::minutes(50), &[Duration::HOUR]),
+            task_due_in("far off", Duration::DAY, &[Duration::HOUR]),
+        ];
+
+        assert!(app.check_reminders());
+        assert_eq!(*sent.borrow(), ["shown", "waiting"]);
+        assert_eq!(app.notices.len(), 2);
+        assert!(matches!(app.popup, Some(Popup::Reminder)));
+
+        assert!(!app.check_reminders());
+        assert_eq!(sent.borrow().len(), 2);
+
+        app.change_active_task_due_date(1);
+        app.reminders.clock = Box::new(FixedClock(NOW + Duration::DAY));
+        assert!(app.check_reminders());
+        assert_eq!(*sent.borrow(), ["shown", "waiting", "shown", "far off"]);
+    }
+
+    #[test]
+    fn reminders_wait_for_the_field_to_lose_focus() {
+        let mut app = App::new();
+        app.reminders.clock = Box::new(FixedClock(NOW));
+        app.tasks = vec![task_due_in("soon", Duration::minutes(5), &[Duration::HOUR])];
+        app.current_screen = CurrentScreen::Editing;
+        app.edit_mode = Some(EditMode::Title);
+
+        assert!(app.check_reminders());
+        assert!(app.popup.is_none());
+        assert_eq!(app.notices.len(), 1);
+
+        app.edit_mode = Some(EditMode::Main);
+        assert!(!app.check_reminders());
+        assert!(matches!(app.popup, Some(Popup::Reminder)));
+    }
+}
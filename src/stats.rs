@@ -0,0 +1,109 @@
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::task::{Task, TaskStatus};
+
+/// Days shown in the completions and open tasks charts.
+pub(crate) const DAYS: usize = 14;
+/// Weeks shown in the weekly completions chart.
+pub(crate) const WEEKS: usize = 12;
+
+/// Figures for the dashboard, worked out from the tasks each time it is drawn.
+pub struct Stats {
+    pub open: usize,
+    pub finished: usize,
+    pub overdue: usize,
+    /// Tasks finished on each of the last `DAYS` days, the oldest first.
+    pub completed_per_day: Vec<(Date, u64)>,
+    /// Tasks finished in each of the last `WEEKS` weeks, the oldest first.
+    pub completed_per_week: Vec<u64>,
+    /// Unfinished tasks at the end of each of the last `DAYS` days.
+    pub open_per_day: Vec<u64>,
+    /// From adding a task to finishing it.
+    pub average_completion: Option<Duration>,
+    /// Share of the tasks due by now that were not finished in time.
+    pub overdue_rate: Option<f64>,
+}
+
+impl Stats {
+    pub(crate) fn new<'a>(tasks: impl Iterator<Item = &'a Task>, now: OffsetDateTime) -> Stats {
+        let tasks: Vec<&Task> = tasks.collect();
+        let today = now.date();
+        let days: Vec<Date> = (0..DAYS as i64)
+            .rev()
+            .map(|days_ago| today - Duration::days(days_ago))
+            .collect();
+        // days are counted in the time zone of `now`
+        let completed_dates: Vec<Date> = tasks
+            .iter()
+            .filter_map(|task| task.completed_at())
+            .map(|time| time.to_offset(now.offset()).date())
+            .collect();
+
+        let completed_per_day = days
+            .iter()
+            .map(|day| {
+                let count = completed_dates.iter().filter(|date| *date == day).count();
+                (*day, count as u64)
+            })
+            .collect();
+        let completed_per_week = (0..WEEKS as i64)
+            .rev()
+            .map(|weeks_ago| {
+                let end = today - Duration::weeks(weeks_ago);
+                let start = end - Duration::days(6);
+                completed_dates
+                    .iter()
+                    .filter(|date| (start..=end).contains(*date))
+                    .count() as u64
+            })
+            .collect();
+        let open_per_day = days
+            .iter()
+            .map(|day| {
+                let end_of_day = (*day + Duration::DAY)
+                    .midnight()
+                    .assume_offset(now.offset());
+                tasks
+                    .iter()
+                    .filter(|task| {
+                        task.time_added < end_of_day
+                            && task.completed_at().is_none_or(|done| done >= end_of_day)
+                    })
+                    .count() as u64
+            })
+            .collect();
+
+        let completion_times: Vec<Duration> = tasks
+            .iter()
+            .filter_map(|task| Some(task.completed_at()? - task.time_added))
+            .collect();
+        let average_completion = (!completion_times.is_empty()).then(|| {
+            completion_times.iter().copied().sum::<Duration>() / completion_times.len() as u32
+        });
+
+        // late are the unfinished ones past due and those finished after their due time
+        let due: Vec<&&Task> = tasks.iter().filter(|task| task.due_time < now).collect();
+        let late = due
+            .iter()
+            .filter(|task| task.completed_at().is_none_or(|done| done > task.due_time))
+            .count();
+        let overdue_rate = (!due.is_empty()).then(|| late as f64 / due.len() as f64);
+
+        Stats {
+            open: tasks
+                .iter()
+                .filter(|task| matches!(task.task_status, TaskStatus::InProgress))
+                .count(),
+            finished: tasks
+                .iter()
+                .filter(|task| matches!(task.task_status, TaskStatus::Finished))
+                .count(),
+            overdue: tasks.iter().filter(|task| task.is_overdue()).count(),
+            completed_per_day,
+            completed_per_week,
+            open_per_day,
+            average_completion,
+            overdue_rate,
+        }
+    }
+}
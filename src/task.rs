@@ -1,136 +1,535 @@
-use std::iter::zip;
+use std::{cmp::Ordering, iter::zip, str::FromStr};
 
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Block, Paragraph, Widget},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, StatefulWidget, Widget, Wrap},
 };
 use serde::{Deserialize, Serialize};
-use time::{Duration, OffsetDateTime};
+use strum::EnumIter;
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::{
+    dates::{duration_text, relative_due, relative_time, DateConfig},
+    markdown::markdown_to_text,
+    theme::Theme,
+};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Task {
+    /// Tells the task apart from the others for as long as it exists, tasks
+    /// saved without one get one when loaded.
+    #[serde(default = "rand::random")]
+    pub(crate) id: u64,
     pub(crate) title: String,
     pub(crate) description: Option<String>,
     pub(crate) task_status: TaskStatus,
     pub(crate) time_added: OffsetDateTime,
     pub(crate) time_edited: OffsetDateTime,
     pub(crate) due_time: OffsetDateTime,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    /// When the task was last marked finished, `None` while it is in progress.
+    #[serde(default)]
+    pub(crate) time_completed: Option<OffsetDateTime>,
+    /// Work periods of the focus timer spent on the task.
+    #[serde(default)]
+    pub(crate) focus_sessions: Vec<FocusSession>,
+    /// Time tracked with the start/stop timer.
+    #[serde(default)]
+    pub(crate) time_entries: Vec<TimeEntry>,
+    /// How long the task is expected to take.
+    #[serde(default)]
+    pub(crate) estimate: Option<Duration>,
+    /// The task is kept out of the way until then.
+    #[serde(default)]
+    pub(crate) start_time: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub(crate) reminders: Vec<Reminder>,
+}
+
+/// A stretch of time tracked on a task, still running while `end` is `None`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub(crate) start: OffsetDateTime,
+    pub(crate) end: Option<OffsetDateTime>,
+}
+
+/// A notice some time before the due time.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub(crate) before: Duration,
+    /// The due time it last went off for, moving the due date sets it again.
+    #[serde(default)]
+    pub(crate) fired_for: Option<OffsetDateTime>,
+}
+
+/// A stretch of time worked on a task with the focus timer.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FocusSession {
+    pub(crate) start: OffsetDateTime,
+    pub(crate) end: OffsetDateTime,
 }
 
 impl Task {
     pub(crate) fn default(title: String) -> Task {
-        let now = OffsetDateTime::now_local().unwrap();
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
         Task {
+            id: rand::random(),
             title,
             description: None,
             task_status: TaskStatus::InProgress,
             time_added: now,
             time_edited: now,
             due_time: now + Duration::DAY,
+            tags: Vec::new(),
+            time_completed: None,
+            focus_sessions: Vec::new(),
+            time_entries: Vec::new(),
+            estimate: None,
+            start_time: None,
+            reminders: Vec::new(),
         }
     }
     pub(crate) fn new(title: String, description: String) -> Task {
-        let now = OffsetDateTime::now_local().unwrap();
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
         Task {
+            id: rand::random(),
             title,
             description: Some(description),
             task_status: TaskStatus::InProgress,
             time_added: now,
             time_edited: now,
             due_time: now + Duration::DAY,
+            tags: Vec::new(),
+            time_completed: None,
+            focus_sessions: Vec::new(),
+            time_entries: Vec::new(),
+            estimate: None,
+            start_time: None,
+            reminders: Vec::new(),
         }
     }
 
     pub(crate) fn change_due_date(&mut self, num_days: i64) {
         self.due_time += Duration::days(num_days);
     }
+
+    /// Whether the task is unfinished past its due time.
+    pub(crate) fn is_overdue(&self) -> bool {
+        matches!(self.task_status, TaskStatus::InProgress)
+            && self.due_time < OffsetDateTime::now_utc()
+    }
+
+    /// Whether the start time is still to come.
+    pub(crate) fn is_deferred(&self) -> bool {
+        self.start_time
+            .is_some_and(|start| start > OffsetDateTime::now_utc())
+    }
+
+    /// Defers the task until `duration` from now.
+    pub(crate) fn snooze(&mut self, duration: Duration) {
+        self.start_time = Some(OffsetDateTime::now_utc() + duration);
+    }
+
+    /// Whether the task is unfinished and due later today, in the time zone
+    /// of its due time.
+    pub(crate) fn is_due_today(&self) -> bool {
+        let now = OffsetDateTime::now_utc().to_offset(self.due_time.offset());
+        matches!(self.task_status, TaskStatus::InProgress)
+            && self.due_time >= now
+            && self.due_time.date() == now.date()
+    }
+
+    /// Changes the status, recording when the task is finished.
+    pub(crate) fn set_status(&mut self, status: TaskStatus) {
+        self.time_completed = match (self.task_status, status) {
+            (TaskStatus::Finished, TaskStatus::Finished) => self.time_completed,
+            (_, TaskStatus::Finished) => Some(OffsetDateTime::now_utc()),
+            (_, TaskStatus::InProgress) => None,
+        };
+        self.task_status = status;
+    }
+
+    /// When the task was finished, tasks finished before completion times were
+    /// recorded count as finished when they were last edited.
+    pub(crate) fn completed_at(&self) -> Option<OffsetDateTime> {
+        match self.task_status {
+            TaskStatus::InProgress => None,
+            TaskStatus::Finished => Some(self.time_completed.unwrap_or(self.time_edited)),
+        }
+    }
+
+    pub(crate) fn is_tracking(&self) -> bool {
+        self.time_entries
+            .last()
+            .is_some_and(|entry| entry.end.is_none())
+    }
+
+    pub(crate) fn start_tracking(&mut self) {
+        if !self.is_tracking() {
+            self.time_entries.push(TimeEntry {
+                start: OffsetDateTime::now_utc(),
+                end: None,
+            });
+        }
+    }
+
+    pub(crate) fn stop_tracking(&mut self) {
+        if let Some(entry) = self.time_entries.last_mut() {
+            entry.end.get_or_insert_with(OffsetDateTime::now_utc);
+        }
+    }
+
+    /// Every tracked stretch of time, the focus sessions included, with a
+    /// running entry counted up to `now`.
+    fn tracked_spans(
+        &self,
+        now: OffsetDateTime,
+    ) -> impl Iterator<Item = (OffsetDateTime, OffsetDateTime)> + '_ {
+        let entries = self
+            .time_entries
+            .iter()
+            .map(move |entry| (entry.start, entry.end.unwrap_or(now)));
+        let sessions = self
+            .focus_sessions
+            .iter()
+            .map(|session| (session.start, session.end));
+        entries.chain(sessions)
+    }
+
+    pub(crate) fn tracked_time(&self, now: OffsetDateTime) -> Duration {
+        self.tracked_spans(now)
+            .map(|(start, end)| end - start)
+            .sum()
+    }
+
+    /// The part of the tracked time that falls between `from` and `to`.
+    pub(crate) fn tracked_between(
+        &self,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+        now: OffsetDateTime,
+    ) -> Duration {
+        self.tracked_spans(now)
+            .map(|(start, end)| end.min(to) - start.max(from))
+            .filter(|span| span.is_positive())
+            .sum()
+    }
+
+    /// Moves the due date to `date`, keeping the time of day.
+    pub(crate) fn set_due_date(&mut self, date: Date) {
+        self.due_time = self.due_time.replace_date(date);
+    }
+
+    /// Adds `tag` unless the task already has it.
+    pub(crate) fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|existing| existing == tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    pub(crate) fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|existing| existing != tag);
+    }
+
+    /// Adds a reminder `before` the due time, unless there is one already.
+    pub(crate) fn add_reminder(&mut self, before: Duration) {
+        if !self
+            .reminders
+            .iter()
+            .any(|reminder| reminder.before == before)
+        {
+            self.reminders.push(Reminder {
+                before,
+                fired_for: None,
+            });
+            self.reminders
+                .sort_by_key(|reminder| std::cmp::Reverse(reminder.before));
+        }
+    }
+
+    /// Marks the reminders that went off by `now`, `true` if there were any.
+    /// Finished tasks stay quiet, deferred ones until they start.
+    pub(crate) fn take_due_reminders(&mut self, now: OffsetDateTime) -> bool {
+        if !matches!(self.task_status, TaskStatus::InProgress)
+            || self.start_time.is_some_and(|start| start > now)
+        {
+            return false;
+        }
+        let due = self.due_time;
+        let mut fired = false;
+        for reminder in &mut self.reminders {
+            if reminder.fired_for != Some(due) && now >= due - reminder.before {
+                reminder.fired_for = Some(due);
+                fired = true;
+            }
+        }
+        fired
+    }
+}
+
+/// What `:sort` orders the tasks by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Due,
+    Added,
+    Edited,
+    Title,
+}
+
+pub(crate) const SORT_KEYS: [SortKey; 4] = [
+    SortKey::Due,
+    SortKey::Added,
+    SortKey::Edited,
+    SortKey::Title,
+];
+
+impl SortKey {
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            SortKey::Due => "due",
+            SortKey::Added => "added",
+            SortKey::Edited => "edited",
+            SortKey::Title => "title",
+        }
+    }
+
+    pub(crate) fn compare(self, a: &Task, b: &Task) -> Ordering {
+        match self {
+            SortKey::Due => a.due_time.cmp(&b.due_time),
+            SortKey::Added => a.time_added.cmp(&b.time_added),
+            // the most recently edited first
+            SortKey::Edited => b.time_edited.cmp(&a.time_edited),
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        SORT_KEYS
+            .iter()
+            .copied()
+            .find(|key| key.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "can not sort by `{}`, try due, added, edited or title",
+                    name
+                )
+            })
+    }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
 pub enum TaskStatus {
     InProgress,
     Finished,
 }
 
 impl TaskStatus {
-    fn to_text(&self) -> String {
+    pub(crate) fn to_text(self) -> String {
         match self {
             TaskStatus::InProgress => "In Progress".to_string(),
             TaskStatus::Finished => "Finished".to_string(),
         }
     }
+
+    /// The checkbox in front of a task in the lists.
+    pub(crate) const fn marker(self) -> &'static str {
+        match self {
+            TaskStatus::InProgress => "[ ]",
+            TaskStatus::Finished => "[x]",
+        }
+    }
 }
 
-impl Widget for Task {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let b = Block::bordered().title("Task");
-        let chunks = Layout::new(
-            Direction::Vertical,
-            [Constraint::Percentage(80), Constraint::Percentage(20)],
-        )
-        .split(b.inner(area));
+/// Renders a task with its description as markdown.
+pub struct TaskView<'a> {
+    pub task: &'a Task,
+    pub theme: &'a Theme,
+    pub dates: &'a DateConfig,
+    /// Draw the outer border as the focused pane.
+    pub focused: bool,
+}
+
+#[derive(Default)]
+pub struct TaskViewState {
+    /// Lines scrolled down in the description, clamped when rendered.
+    pub description_scroll: u16,
+}
 
+/// Below this height the dates are one line of text instead of bordered blocks.
+const COMPACT_HEIGHT: u16 = 16;
+/// The width a bordered date block needs to show the whole date.
+const DATE_BLOCK_WIDTH: u16 = 25;
+
+impl StatefulWidget for TaskView<'_> {
+    type State = TaskViewState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TaskViewState) {
+        let task = self.task;
+        let theme = self.theme;
+        let bordered = |title| Block::bordered().title(title).border_style(theme.border);
+        let mut b = bordered("Task").border_style(if self.focused {
+            theme.focused_border
+        } else {
+            theme.border
+        });
+        if task.is_overdue() {
+            b = b.title(Line::styled(" overdue ", theme.overdue).right_aligned());
+        } else if task.is_due_today() {
+            b = b.title(Line::styled(" due today ", theme.due_today).right_aligned());
+        }
+        if let Some(start) = task.start_time.filter(|_| task.is_deferred()) {
+            let starts = relative_time(start, OffsetDateTime::now_utc());
+            b = b.title(
+                Line::from(format!(" starts {} ", starts))
+                    .dim()
+                    .right_aligned(),
+            );
+        }
+        let inner = b.inner(area);
         b.render(area, buf);
-        let task_completion =
-            Paragraph::new(self.task_status.to_text()).block(Block::bordered().title("Status"));
-
-        let date_added = Paragraph::new(format!(
-            "{}:{}:{} {}",
-            self.time_added.hour(),
-            self.time_added.minute(),
-            self.time_added.second(),
-            self.time_added.date()
-        ))
-        .block(Block::bordered().title("Added"));
-
-        let date_edited = Paragraph::new(format!(
-            "{}:{}:{} {}",
-            self.time_edited.hour(),
-            self.time_edited.minute(),
-            self.time_edited.second(),
-            self.time_edited.date()
-        ))
-        .block(Block::bordered().title("Edited"));
-
-        let due_date = Paragraph::new(format!(
-            "{}:{}:{} {}",
-            self.due_time.hour(),
-            self.due_time.minute(),
-            self.due_time.second(),
-            self.due_time.date()
-        ))
-        .block(Block::bordered().title("Due"));
-
-        let status_chunks = Layout::new(
-            Direction::Horizontal,
-            [
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-                Constraint::Percentage(25),
-            ],
-        )
-        .split(chunks[1]);
-
-        for (date_widget, status_chunk) in zip(
-            [task_completion, date_added, date_edited, due_date],
-            status_chunks.iter(),
-        ) {
-            date_widget.render(*status_chunk, buf);
-        }
-        let title = Paragraph::new(self.title.clone()).block(Block::bordered().title("Title"));
-        if let Some(desc_text) = self.description {
-            let task_chunks =
-                Layout::vertical([Constraint::Percentage(70), Constraint::Percentage(30)])
-                    .split(chunks[0]);
-            let description =
-                Paragraph::new(desc_text.clone()).block(Block::bordered().title("Description"));
-            for (task_widget, task_chunk) in zip([title, description], task_chunks.iter()) {
-                task_widget.render(*task_chunk, buf);
+
+        let status_style = match task.task_status {
+            TaskStatus::InProgress => theme.status_in_progress,
+            TaskStatus::Finished => theme.status_finished,
+        };
+        let due_style = theme.due_style(task);
+        let now = OffsetDateTime::now_utc();
+        let dates = self.dates;
+        let relative = |text: String| dates.relative.then_some(text);
+        // the most important first, a compact line cuts off the rest
+        let fields = [
+            ("Status", task.task_status.to_text(), None, status_style),
+            (
+                "Due",
+                dates.format_date_time(task.due_time),
+                relative(relative_due(task.due_time, now, task.is_overdue())),
+                due_style,
+            ),
+            (
+                "Added",
+                dates.format_date_time(task.time_added),
+                relative(relative_time(task.time_added, now)),
+                Style::new(),
+            ),
+            (
+                "Edited",
+                dates.format_date_time(task.time_edited),
+                relative(relative_time(task.time_edited, now)),
+                Style::new(),
+            ),
+        ];
+
+        // the relative dates go on a second line inside the blocks
+        let block_height = if dates.relative { 4 } else { 3 };
+        let (rows, columns) = if inner.height < COMPACT_HEIGHT {
+            (0, 0)
+        } else if inner.width >= DATE_BLOCK_WIDTH * 4 {
+            (1, 4)
+        } else {
+            (2, 2)
+        };
+        let fields_height = if rows == 0 { 1 } else { rows * block_height };
+        let [content_area, fields_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(fields_height)]).areas(inner);
+
+        if rows == 0 {
+            let mut spans = Vec::new();
+            for (name, absolute, relative, style) in fields {
+                if !spans.is_empty() {
+                    spans.push(Span::raw(" │ ").style(theme.border));
+                }
+                spans.push(Span::raw(format!("{} ", name)).dim());
+                spans.push(Span::styled(relative.unwrap_or(absolute), style));
             }
+            Paragraph::new(Line::from(spans)).render(fields_area, buf);
+        } else {
+            // one row of four blocks when they fit, two rows of two otherwise
+            let rows = Layout::vertical(vec![Constraint::Length(block_height); rows as usize])
+                .split(fields_area);
+            let cells = rows.iter().flat_map(|row| {
+                Layout::horizontal(vec![Constraint::Ratio(1, columns); columns as usize])
+                    .split(*row)
+                    .to_vec()
+            });
+            for ((name, absolute, relative, style), cell) in zip(fields, cells) {
+                let mut lines = vec![Line::from(absolute)];
+                lines.extend(relative.map(|text| Line::from(text).dim()));
+                Paragraph::new(lines)
+                    .style(style)
+                    .block(bordered(name))
+                    .render(cell, buf);
+            }
+        }
+
+        let mut title_block = bordered("Title");
+        let mut labels: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        if !task.reminders.is_empty() {
+            let before: Vec<String> = task
+                .reminders
+                .iter()
+                .map(|reminder| duration_text(reminder.before))
+                .collect();
+            labels.push(format!("remind {} before", before.join(", ")));
+        }
+        if !labels.is_empty() {
+            title_block = title_block.title_bottom(labels.join(" "));
+        }
+        let tracked = task.tracked_time(now);
+        if tracked.is_positive() || task.estimate.is_some() || task.is_tracking() {
+            let mut text = format!("tracked {}", duration_text(tracked));
+            if let Some(estimate) = task.estimate {
+                text.push_str(&format!(" of {}", duration_text(estimate)));
+            }
+            match task.focus_sessions.len() {
+                0 => {}
+                1 => text.push_str(", 1 session"),
+                n => text.push_str(&format!(", {} sessions", n)),
+            }
+            if task.is_tracking() {
+                text.push_str(" ●");
+            }
+            let style = if task.estimate.is_some_and(|estimate| tracked > estimate) {
+                theme.overdue
+            } else {
+                Style::new()
+            };
+            title_block = title_block.title_bottom(Line::styled(text, style).right_aligned());
+        }
+        let title = Paragraph::new(task.title.clone())
+            .wrap(Wrap { trim: true })
+            .block(title_block);
+        if let Some(desc_text) = &task.description {
+            // the title takes what it needs, up to a third, the description the rest
+            let title_height = (title.line_count(content_area.width.saturating_sub(2)) as u16)
+                .clamp(3, (content_area.height / 3).max(3));
+            let [title_area, description_area_outer] =
+                Layout::vertical([Constraint::Length(title_height), Constraint::Fill(1)])
+                    .areas(content_area);
+            let description_block = bordered("Description");
+            let description_area = description_block.inner(description_area_outer);
+            let description = Paragraph::new(markdown_to_text(desc_text, theme))
+                .wrap(Wrap { trim: false })
+                .block(description_block);
+
+            // the line count includes the block borders, so compare with the outer height
+            let overflow = (description.line_count(description_area.width) as u16)
+                .saturating_sub(description_area_outer.height);
+            state.description_scroll = state.description_scroll.min(overflow);
+
+            title.render(title_area, buf);
+            description
+                .scroll((state.description_scroll, 0))
+                .render(description_area_outer, buf);
         } else {
-            title.render(chunks[0], buf);
+            title.render(content_area, buf);
         }
     }
 }
@@ -0,0 +1,537 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::input_keys::DELETE_CHAR_KEYCODE;
+
+/// An editable block of text with a cursor.
+///
+/// The cursor is stored as a `(row, column)` pair where the column counts
+/// characters, not bytes. The text is serialized as a plain string so the
+/// data file stays readable.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct TextArea {
+    lines: Vec<String>,
+    cursor: (usize, usize),
+    highlight: Option<((usize, usize), (usize, usize))>,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        TextArea {
+            lines: vec![String::new()],
+            cursor: (0, 0),
+            highlight: None,
+        }
+    }
+}
+
+impl From<String> for TextArea {
+    fn from(text: String) -> Self {
+        let mut text_area = TextArea::default();
+        text_area.set_text(&text);
+        text_area
+    }
+}
+
+impl From<TextArea> for String {
+    fn from(text_area: TextArea) -> Self {
+        text_area.text()
+    }
+}
+
+impl TextArea {
+    pub(crate) fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| line.is_empty())
+    }
+
+    /// Replaces the contents and moves the cursor to the end of the text.
+    pub(crate) fn set_text(&mut self, text: &str) {
+        self.lines = text.split('\n').map(str::to_string).collect();
+        let row = self.lines.len() - 1;
+        self.cursor = (row, self.line_len(row));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.lines = vec![String::new()];
+        self.cursor = (0, 0);
+        self.highlight = None;
+    }
+
+    pub(crate) fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub(crate) fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Moves the cursor, clamping it to the text.
+    pub(crate) fn set_cursor(&mut self, (row, col): (usize, usize)) {
+        let row = row.min(self.lines.len() - 1);
+        self.cursor = (row, col.min(self.line_len(row)));
+    }
+
+    /// Highlights the text from `start` up to, but not including, `end`.
+    pub(crate) fn set_highlight(&mut self, highlight: Option<((usize, usize), (usize, usize))>) {
+        self.highlight = highlight;
+    }
+
+    pub(crate) fn line_len(&self, row: usize) -> usize {
+        self.lines.get(row).map_or(0, |line| line.chars().count())
+    }
+
+    /// Text from `start` up to, but not including, `end`.
+    pub(crate) fn text_between(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        let mut text = String::new();
+        for row in start.0..=end.0.min(self.lines.len() - 1) {
+            let line = &self.lines[row];
+            let from = if row == start.0 { start.1 } else { 0 };
+            let to = if row == end.0 { end.1 } else { usize::MAX };
+            text.push_str(
+                &line[byte_index(line, from)..byte_index(line, to).max(byte_index(line, from))],
+            );
+            if row < end.0 {
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    /// Removes the text from `start` up to, but not including, `end` and
+    /// leaves the cursor at `start`. Returns the removed text.
+    pub(crate) fn delete_between(&mut self, start: (usize, usize), end: (usize, usize)) -> String {
+        let end = (end.0.min(self.lines.len() - 1), end.1);
+        let removed = self.text_between(start, end);
+        let tail = {
+            let line = &self.lines[end.0];
+            line[byte_index(line, end.1)..].to_string()
+        };
+        let head_index = byte_index(&self.lines[start.0], start.1);
+        self.lines[start.0].truncate(head_index);
+        self.lines[start.0].push_str(&tail);
+        self.lines.drain(start.0 + 1..=end.0);
+        self.set_cursor(start);
+        removed
+    }
+
+    /// Removes whole lines `first..=last`, always keeping at least one line.
+    pub(crate) fn delete_lines(&mut self, first: usize, last: usize) -> String {
+        let last = last.min(self.lines.len() - 1);
+        let removed = self
+            .lines
+            .drain(first..=last)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.set_cursor((first, 0));
+        removed
+    }
+
+    /// Inserts `text` as whole lines starting at `row`.
+    pub(crate) fn insert_lines(&mut self, row: usize, text: &str) {
+        let row = row.min(self.lines.len());
+        for (i, line) in text.split('\n').enumerate() {
+            self.lines.insert(row + i, line.to_string());
+        }
+        self.set_cursor((row, 0));
+    }
+
+    /// Handles an editing key. Returns `true` if the key was consumed.
+    ///
+    /// Enter is not handled here since whether a field accepts newlines is
+    /// up to the caller, see [`TextArea::insert_newline`].
+    pub(crate) fn input(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_word_back(),
+            KeyCode::Char('b') if alt => self.move_word_back(),
+            KeyCode::Char('f') if alt => self.move_word_forward(),
+            KeyCode::Char(ch) if !ctrl && !alt => self.insert_char(ch),
+            DELETE_CHAR_KEYCODE => self.delete_char_back(),
+            KeyCode::Delete => self.delete_char_forward(),
+            KeyCode::Left if ctrl => self.move_word_back(),
+            KeyCode::Right if ctrl => self.move_word_forward(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Up => self.move_up(),
+            KeyCode::Down => self.move_down(),
+            KeyCode::Home if ctrl => self.cursor = (0, 0),
+            KeyCode::End if ctrl => {
+                let row = self.lines.len() - 1;
+                self.cursor = (row, self.line_len(row));
+            }
+            KeyCode::Home => self.move_line_start(),
+            KeyCode::End => self.move_line_end(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub(crate) fn insert_char(&mut self, ch: char) {
+        let (row, col) = self.cursor;
+        let index = byte_index(&self.lines[row], col);
+        self.lines[row].insert(index, ch);
+        self.cursor.1 += 1;
+    }
+
+    pub(crate) fn insert_newline(&mut self) {
+        let (row, col) = self.cursor;
+        let index = byte_index(&self.lines[row], col);
+        let rest = self.lines[row].split_off(index);
+        self.lines.insert(row + 1, rest);
+        self.cursor = (row + 1, 0);
+    }
+
+    /// Inserts `text` at the cursor, e.g. from a bracketed paste. A field
+    /// that is a single line gets spaces in place of the line breaks.
+    pub(crate) fn insert_str(&mut self, text: &str, multiline: bool) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        for ch in text.chars() {
+            match ch {
+                '\n' if multiline => self.insert_newline(),
+                '\n' => self.insert_char(' '),
+                ch => self.insert_char(ch),
+            }
+        }
+    }
+
+    pub(crate) fn delete_char_back(&mut self) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            let index = byte_index(&self.lines[row], col - 1);
+            self.lines[row].remove(index);
+            self.cursor.1 -= 1;
+        } else if row > 0 {
+            let line = self.lines.remove(row);
+            let new_col = self.line_len(row - 1);
+            self.lines[row - 1].push_str(&line);
+            self.cursor = (row - 1, new_col);
+        }
+    }
+
+    pub(crate) fn delete_char_forward(&mut self) {
+        let (row, col) = self.cursor;
+        if col < self.line_len(row) {
+            let index = byte_index(&self.lines[row], col);
+            self.lines[row].remove(index);
+        } else if row + 1 < self.lines.len() {
+            let line = self.lines.remove(row + 1);
+            self.lines[row].push_str(&line);
+        }
+    }
+
+    /// Deletes from the start of the previous word up to the cursor.
+    pub(crate) fn delete_word_back(&mut self) {
+        let (row, col) = self.cursor;
+        if col == 0 {
+            self.delete_char_back();
+            return;
+        }
+        let start = previous_word_start(&self.lines[row], col);
+        let start_index = byte_index(&self.lines[row], start);
+        let end_index = byte_index(&self.lines[row], col);
+        self.lines[row].replace_range(start_index..end_index, "");
+        self.cursor.1 = start;
+    }
+
+    pub(crate) fn move_left(&mut self) {
+        let (row, col) = self.cursor;
+        if col > 0 {
+            self.cursor.1 -= 1;
+        } else if row > 0 {
+            self.cursor = (row - 1, self.line_len(row - 1));
+        }
+    }
+
+    pub(crate) fn move_right(&mut self) {
+        let (row, col) = self.cursor;
+        if col < self.line_len(row) {
+            self.cursor.1 += 1;
+        } else if row + 1 < self.lines.len() {
+            self.cursor = (row + 1, 0);
+        }
+    }
+
+    pub(crate) fn move_up(&mut self) {
+        let (row, col) = self.cursor;
+        if row > 0 {
+            self.cursor = (row - 1, col.min(self.line_len(row - 1)));
+        }
+    }
+
+    pub(crate) fn move_down(&mut self) {
+        let (row, col) = self.cursor;
+        if row + 1 < self.lines.len() {
+            self.cursor = (row + 1, col.min(self.line_len(row + 1)));
+        }
+    }
+
+    pub(crate) fn move_line_start(&mut self) {
+        self.cursor.1 = 0;
+    }
+
+    pub(crate) fn move_line_end(&mut self) {
+        self.cursor.1 = self.line_len(self.cursor.0);
+    }
+
+    pub(crate) fn move_word_back(&mut self) {
+        self.cursor = self.previous_word_position();
+    }
+
+    pub(crate) fn move_word_forward(&mut self) {
+        self.cursor = self.next_word_position();
+    }
+
+    /// Start of the word before the cursor, wrapping to the previous line.
+    pub(crate) fn previous_word_position(&self) -> (usize, usize) {
+        let (row, col) = self.cursor;
+        match (row, col) {
+            (0, 0) => (0, 0),
+            (row, 0) => (row - 1, self.line_len(row - 1)),
+            (row, col) => (row, previous_word_start(&self.lines[row], col)),
+        }
+    }
+
+    /// Start of the word after the cursor, wrapping to the next line.
+    pub(crate) fn next_word_position(&self) -> (usize, usize) {
+        let (row, col) = self.cursor;
+        if col < self.line_len(row) {
+            (row, next_word_start(&self.lines[row], col))
+        } else if row + 1 < self.lines.len() {
+            (row + 1, 0)
+        } else {
+            (row, col)
+        }
+    }
+
+    /// Screen position of the cursor when the text is rendered into `area`.
+    pub(crate) fn cursor_position(&self, area: Rect) -> Position {
+        let (row, col) = self.cursor;
+        let (row_offset, col_offset) = self.scroll_offset(area);
+        let line = &self.lines[row];
+        let prefix = &line[..byte_index(line, col)];
+        let x = prefix.width().saturating_sub(col_offset) as u16;
+        let y = row.saturating_sub(row_offset) as u16;
+        Position::new(
+            area.x + x.min(area.width.saturating_sub(1)),
+            area.y + y.min(area.height.saturating_sub(1)),
+        )
+    }
+
+    /// Scrolls just enough to keep the cursor inside `area`.
+    fn scroll_offset(&self, area: Rect) -> (usize, usize) {
+        let (row, col) = self.cursor;
+        let line = &self.lines[row];
+        let cursor_x = line[..byte_index(line, col)].width();
+        (
+            (row + 1).saturating_sub(area.height as usize),
+            (cursor_x + 1).saturating_sub(area.width as usize),
+        )
+    }
+}
+
+impl TextArea {
+    fn render_line<'a>(&self, row: usize, line: &'a str) -> Line<'a> {
+        let Some((start, end)) = self.highlight else {
+            return Line::from(line);
+        };
+        if row < start.0 || row > end.0 {
+            return Line::from(line);
+        }
+        let from = if row == start.0 { start.1 } else { 0 };
+        let to = if row == end.0 { end.1 } else { usize::MAX };
+        let (from, to) = (
+            byte_index(line, from),
+            byte_index(line, to).max(byte_index(line, from)),
+        );
+        let highlighted = Style::default().add_modifier(Modifier::REVERSED);
+        Line::from(vec![
+            Span::raw(&line[..from]),
+            Span::styled(&line[from..to], highlighted),
+            Span::raw(&line[to..]),
+        ])
+    }
+}
+
+impl Widget for &TextArea {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (row_offset, col_offset) = self.scroll_offset(area);
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(row, line)| self.render_line(row, line))
+            .collect();
+        Paragraph::new(lines)
+            .scroll((row_offset as u16, col_offset as u16))
+            .render(area, buf);
+    }
+}
+
+/// Byte index of the `col`th character in `line`, clamped to the line end.
+pub(crate) fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices()
+        .nth(col)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+fn previous_word_start(line: &str, col: usize) -> usize {
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = col.min(chars.len());
+    while i > 0 && chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    while i > 0 && !chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    i
+}
+
+fn next_word_start(line: &str, col: usize) -> usize {
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = col;
+    while i < chars.len() && !chars[i].is_whitespace() {
+        i += 1;
+    }
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(text_area: &mut TextArea, code: KeyCode, modifiers: KeyModifiers) {
+        assert!(text_area.input(KeyEvent::new(code, modifiers)));
+    }
+
+    fn text_area(text: &str, cursor: (usize, usize)) -> TextArea {
+        let mut text_area = TextArea::from(text.to_string());
+        text_area.set_cursor(cursor);
+        text_area
+    }
+
+    #[test]
+    fn multibyte_text() {
+        let mut text = text_area("héllo wörld", (0, 2));
+        text.insert_char('ß');
+        assert_eq!(text.text(), "héßllo wörld");
+        assert_eq!(text.cursor(), (0, 3));
+
+        press(&mut text, DELETE_CHAR_KEYCODE, KeyModifiers::NONE);
+        press(&mut text, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(text.text(), "hélo wörld");
+        assert_eq!(text.text_between((0, 1), (0, 8)), "élo wör");
+
+        assert_eq!(text.delete_between((0, 5), (0, 8)), "wör");
+        assert_eq!(text.text(), "hélo ld");
+        assert_eq!(text.cursor(), (0, 5));
+
+        // wide characters take two columns on screen
+        let text = text_area("日本語", (0, 2));
+        let area = Rect::new(0, 0, 20, 1);
+        assert_eq!(text.cursor_position(area), Position::new(4, 0));
+    }
+
+    #[test]
+    fn enter_splits_and_backspace_joins_lines() {
+        let mut text = text_area("ab", (0, 1));
+        text.insert_newline();
+        assert_eq!(text.lines(), ["a", "b"]);
+        assert_eq!(text.cursor(), (1, 0));
+
+        press(&mut text, DELETE_CHAR_KEYCODE, KeyModifiers::NONE);
+        assert_eq!(text.lines(), ["ab"]);
+        assert_eq!(text.cursor(), (0, 1));
+
+        // nothing before the first line to join with
+        text.set_cursor((0, 0));
+        press(&mut text, DELETE_CHAR_KEYCODE, KeyModifiers::NONE);
+        assert_eq!(text.text(), "ab");
+    }
+
+    #[test]
+    fn delete_word_back() {
+        let mut text = text_area("one two  three", (0, 14));
+        press(&mut text, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(text.text(), "one two  ");
+        press(&mut text, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(text.text(), "one ");
+
+        // at the start of a line it joins the line with the one before
+        let mut text = text_area("one\ntwo", (1, 0));
+        press(&mut text, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(text.lines(), ["onetwo"]);
+        assert_eq!(text.cursor(), (0, 3));
+    }
+
+    #[test]
+    fn pasting_lines() {
+        let mut text = text_area("[]", (0, 1));
+        text.insert_str("one\r\ntwo\rthree", true);
+        assert_eq!(text.lines(), ["[one", "two", "three]"]);
+        assert_eq!(text.cursor(), (2, 5));
+
+        let mut text = text_area("[]", (0, 1));
+        text.insert_str("one\r\ntwo\rthree", false);
+        assert_eq!(text.lines(), ["[one two three]"]);
+        assert_eq!(text.cursor(), (0, 14));
+    }
+
+    #[test]
+    fn word_motions_wrap_lines() {
+        let mut text = text_area("one two\nthree", (0, 0));
+        text.move_word_forward();
+        assert_eq!(text.cursor(), (0, 4));
+        text.move_word_forward();
+        assert_eq!(text.cursor(), (0, 7));
+        text.move_word_forward();
+        assert_eq!(text.cursor(), (1, 0));
+        text.move_word_back();
+        assert_eq!(text.cursor(), (0, 7));
+        text.move_word_back();
+        assert_eq!(text.cursor(), (0, 4));
+    }
+
+    #[test]
+    fn deleting_lines_keeps_one() {
+        let mut text = text_area("one\ntwo\nthree", (2, 3));
+        assert_eq!(text.delete_lines(1, 5), "two\nthree");
+        assert_eq!(text.lines(), ["one"]);
+        assert_eq!(text.cursor(), (0, 0));
+        assert_eq!(text.delete_lines(0, 0), "one");
+        assert_eq!(text.lines(), [""]);
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn cursor_is_clamped_and_scrolled() {
+        let mut text = text_area("short\nmuch longer", (1, 11));
+        press(&mut text, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(text.cursor(), (0, 5));
+        text.set_cursor((9, 99));
+        assert_eq!(text.cursor(), (1, 11));
+
+        // the view scrolls to keep the cursor inside a small area
+        let area = Rect::new(10, 5, 4, 1);
+        assert_eq!(text.cursor_position(area), Position::new(13, 5));
+    }
+}
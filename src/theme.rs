@@ -0,0 +1,354 @@
+use std::{collections::BTreeMap, env, str::FromStr};
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+use serde::Deserialize;
+
+use crate::task::Task;
+
+/// The styles of every themed part of the screen.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub border: Style,
+    /// The border of the pane the resize keys apply to.
+    pub focused_border: Style,
+    /// The background of the editing screen.
+    pub edit_background: Style,
+    /// The title or description field being typed into.
+    pub active_field: Style,
+    pub status_in_progress: Style,
+    pub status_finished: Style,
+    /// Due dates in the past of unfinished tasks.
+    pub overdue: Style,
+    /// Unfinished tasks due later today.
+    pub due_today: Style,
+    pub calendar_today: Style,
+    pub calendar_due: Style,
+    /// The highlighted entry of a list.
+    pub selected: Style,
+    pub error: Style,
+    /// Inline code and code blocks in descriptions.
+    pub code: Style,
+    /// Link text in descriptions.
+    pub link: Style,
+    /// The less important parts of descriptions, e.g. link urls and rules.
+    pub muted: Style,
+    /// Strip every colour when drawing, set by `NO_COLOR`.
+    pub no_color: bool,
+}
+
+/// Names of the built-in themes, in the order they are listed in errors.
+const THEME_NAMES: [&str; 4] = ["dark", "light", "high-contrast", "solarized"];
+
+/// Names of the styles a theme can override in `[theme.styles]`.
+const ELEMENT_NAMES: [&str; 15] = [
+    "border",
+    "focused_border",
+    "edit_background",
+    "active_field",
+    "status_in_progress",
+    "status_finished",
+    "overdue",
+    "due_today",
+    "calendar_today",
+    "calendar_due",
+    "selected",
+    "error",
+    "code",
+    "link",
+    "muted",
+];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub(crate) fn dark() -> Theme {
+        Theme {
+            border: Style::new(),
+            focused_border: Style::new().fg(Color::Cyan),
+            edit_background: Style::new().bg(Color::DarkGray),
+            active_field: Style::new().bg(Color::LightYellow).fg(Color::Black),
+            status_in_progress: Style::new().fg(Color::Yellow),
+            status_finished: Style::new().fg(Color::Green),
+            overdue: Style::new()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            due_today: Style::new()
+                .fg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            calendar_today: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            calendar_due: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            selected: Style::new().add_modifier(Modifier::REVERSED),
+            error: Style::new().fg(Color::Red),
+            code: Style::new().fg(Color::Yellow),
+            link: Style::new()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::UNDERLINED),
+            muted: Style::new().fg(Color::DarkGray),
+            no_color: false,
+        }
+    }
+
+    pub(crate) fn light() -> Theme {
+        Theme {
+            border: Style::new().fg(Color::DarkGray),
+            focused_border: Style::new().fg(Color::Blue),
+            edit_background: Style::new().bg(Color::Rgb(230, 230, 230)).fg(Color::Black),
+            active_field: Style::new().bg(Color::Rgb(255, 244, 179)).fg(Color::Black),
+            status_in_progress: Style::new().fg(Color::Rgb(175, 115, 0)),
+            status_finished: Style::new().fg(Color::Rgb(0, 135, 0)),
+            overdue: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            due_today: Style::new()
+                .fg(Color::Rgb(175, 95, 0))
+                .add_modifier(Modifier::BOLD),
+            calendar_today: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            calendar_due: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            selected: Style::new().bg(Color::Rgb(200, 220, 255)).fg(Color::Black),
+            error: Style::new().fg(Color::Red),
+            code: Style::new().fg(Color::Rgb(160, 60, 0)),
+            link: Style::new()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::UNDERLINED),
+            muted: Style::new().fg(Color::Rgb(120, 120, 120)),
+            no_color: false,
+        }
+    }
+
+    pub(crate) fn high_contrast() -> Theme {
+        let bold = Style::new().add_modifier(Modifier::BOLD);
+        Theme {
+            border: bold.fg(Color::White),
+            focused_border: bold.fg(Color::Yellow),
+            edit_background: Style::new().bg(Color::Black).fg(Color::White),
+            active_field: bold.bg(Color::Yellow).fg(Color::Black),
+            status_in_progress: bold.fg(Color::LightYellow),
+            status_finished: bold.fg(Color::LightGreen),
+            overdue: bold.bg(Color::Red).fg(Color::White),
+            due_today: bold.bg(Color::Yellow).fg(Color::Black),
+            calendar_today: bold.bg(Color::White).fg(Color::Black),
+            calendar_due: bold.bg(Color::LightCyan).fg(Color::Black),
+            selected: bold.bg(Color::White).fg(Color::Black),
+            error: bold.fg(Color::LightRed),
+            code: bold.fg(Color::LightYellow),
+            link: bold.fg(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+            muted: Style::new().fg(Color::White),
+            no_color: false,
+        }
+    }
+
+    pub(crate) fn solarized() -> Theme {
+        let base03 = Color::Rgb(0x00, 0x2b, 0x36);
+        let base02 = Color::Rgb(0x07, 0x36, 0x42);
+        let base01 = Color::Rgb(0x58, 0x6e, 0x75);
+        let base0 = Color::Rgb(0x83, 0x94, 0x96);
+        let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+        let orange = Color::Rgb(0xcb, 0x4b, 0x16);
+        let red = Color::Rgb(0xdc, 0x32, 0x2f);
+        let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+        let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+        let green = Color::Rgb(0x85, 0x99, 0x00);
+        Theme {
+            border: Style::new().fg(base01),
+            focused_border: Style::new().fg(blue),
+            edit_background: Style::new().bg(base02).fg(base0),
+            active_field: Style::new().bg(yellow).fg(base03),
+            status_in_progress: Style::new().fg(yellow),
+            status_finished: Style::new().fg(green),
+            overdue: Style::new().fg(red).add_modifier(Modifier::BOLD),
+            due_today: Style::new().fg(orange).add_modifier(Modifier::BOLD),
+            calendar_today: Style::new().fg(red).add_modifier(Modifier::BOLD),
+            calendar_due: Style::new().fg(blue).add_modifier(Modifier::BOLD),
+            selected: Style::new().bg(base02).fg(cyan),
+            error: Style::new().fg(red),
+            code: Style::new().fg(orange),
+            link: Style::new().fg(blue).add_modifier(Modifier::UNDERLINED),
+            muted: Style::new().fg(base01),
+            no_color: false,
+        }
+    }
+
+    /// Used for `NO_COLOR`, telling things apart with modifiers alone.
+    pub(crate) fn monochrome() -> Theme {
+        Theme {
+            border: Style::new(),
+            focused_border: Style::new().add_modifier(Modifier::BOLD),
+            edit_background: Style::new(),
+            active_field: Style::new().add_modifier(Modifier::REVERSED),
+            status_in_progress: Style::new(),
+            status_finished: Style::new().add_modifier(Modifier::DIM),
+            overdue: Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            due_today: Style::new().add_modifier(Modifier::BOLD),
+            calendar_today: Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            calendar_due: Style::new().add_modifier(Modifier::REVERSED),
+            selected: Style::new().add_modifier(Modifier::REVERSED),
+            error: Style::new().add_modifier(Modifier::BOLD),
+            code: Style::new(),
+            link: Style::new().add_modifier(Modifier::UNDERLINED),
+            muted: Style::new().add_modifier(Modifier::DIM),
+            no_color: true,
+        }
+    }
+
+    /// Builds the theme named in the config with its style overrides applied,
+    /// along with any problems found.
+    ///
+    /// The theme is built from everything that was valid, so a typo in one
+    /// style keeps `NO_COLOR` and the chosen theme.
+    pub(crate) fn new(config: &ThemeConfig) -> (Theme, Option<String>) {
+        let mut errors = Vec::new();
+        let mut theme = match config.name.as_str() {
+            "dark" => Theme::dark(),
+            "light" => Theme::light(),
+            "high-contrast" => Theme::high_contrast(),
+            "solarized" => Theme::solarized(),
+            name => {
+                errors.push(format!(
+                    "unknown theme `{}`, try one of {}",
+                    name,
+                    THEME_NAMES.join(", ")
+                ));
+                Theme::dark()
+            }
+        };
+        if no_color_requested() {
+            theme = Theme::monochrome();
+        }
+
+        for (name, style_config) in &config.styles {
+            let result = style_config.to_style().and_then(|style| {
+                let element = theme.element_mut(name).ok_or_else(|| {
+                    format!("unknown element, try one of {}", ELEMENT_NAMES.join(", "))
+                })?;
+                *element = style;
+                Ok(())
+            });
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", name, e));
+            }
+        }
+
+        let errors = (!errors.is_empty()).then(|| errors.join("\n"));
+        (theme, errors)
+    }
+
+    fn element_mut(&mut self, name: &str) -> Option<&mut Style> {
+        let element = match name {
+            "border" => &mut self.border,
+            "focused_border" => &mut self.focused_border,
+            "edit_background" => &mut self.edit_background,
+            "active_field" => &mut self.active_field,
+            "status_in_progress" => &mut self.status_in_progress,
+            "status_finished" => &mut self.status_finished,
+            "overdue" => &mut self.overdue,
+            "due_today" => &mut self.due_today,
+            "calendar_today" => &mut self.calendar_today,
+            "calendar_due" => &mut self.calendar_due,
+            "selected" => &mut self.selected,
+            "error" => &mut self.error,
+            "code" => &mut self.code,
+            "link" => &mut self.link,
+            "muted" => &mut self.muted,
+            _ => return None,
+        };
+        Some(element)
+    }
+
+    /// How a task is marked wherever it is listed, by how close its due time is.
+    pub(crate) fn due_style(&self, task: &Task) -> Style {
+        if task.is_overdue() {
+            self.overdue
+        } else if task.is_due_today() {
+            self.due_today
+        } else {
+            Style::new()
+        }
+    }
+
+    /// Removes the colours the widgets drew on their own, like the markdown
+    /// code spans, when `NO_COLOR` is set.
+    pub(crate) fn strip_colors(&self, buf: &mut Buffer) {
+        if !self.no_color {
+            return;
+        }
+        for cell in buf.content.iter_mut() {
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+            cell.underline_color = Color::Reset;
+        }
+    }
+}
+
+/// `NO_COLOR` set to anything but an empty string turns colours off, see no-color.org.
+fn no_color_requested() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// The `[theme]` config section.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// One of the built-in themes the styles are applied on top of.
+    pub name: String,
+    pub styles: BTreeMap<String, StyleConfig>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig {
+            name: "dark".to_string(),
+            styles: BTreeMap::new(),
+        }
+    }
+}
+
+/// A style in the config, e.g. `{ fg = "black", bg = "#b58900", modifiers = ["bold"] }`.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct StyleConfig {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub modifiers: Vec<String>,
+}
+
+impl StyleConfig {
+    fn to_style(&self) -> Result<Style, String> {
+        let mut style = Style::new();
+        if let Some(fg) = &self.fg {
+            style = style.fg(parse_color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(parse_color(bg)?);
+        }
+        for modifier in &self.modifiers {
+            style = style.add_modifier(parse_modifier(modifier)?);
+        }
+        Ok(style)
+    }
+}
+
+/// Colour names like `lightyellow`, `#b58900` or an index into the 256 colours.
+fn parse_color(color: &str) -> Result<Color, String> {
+    Color::from_str(color).map_err(|_| format!("unknown colour `{}`", color))
+}
+
+fn parse_modifier(modifier: &str) -> Result<Modifier, String> {
+    let modifier = match modifier.to_ascii_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" => Modifier::UNDERLINED,
+        "reversed" => Modifier::REVERSED,
+        "crossed_out" => Modifier::CROSSED_OUT,
+        "slow_blink" => Modifier::SLOW_BLINK,
+        "rapid_blink" => Modifier::RAPID_BLINK,
+        "hidden" => Modifier::HIDDEN,
+        _ => return Err(format!("unknown modifier `{}`", modifier)),
+    };
+    Ok(modifier)
+}
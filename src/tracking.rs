@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::task::Task;
+
+/// Time spent per task and per tag between two days, for the `:report` command.
+pub struct TimeReport {
+    pub from: Date,
+    /// The last day of the report, included.
+    pub to: Date,
+    /// Task titles with their time, the longest first.
+    pub tasks: Vec<(String, Duration)>,
+    /// Tags by name with the time of the tasks carrying them.
+    pub tags: Vec<(String, Duration)>,
+    pub total: Duration,
+}
+
+impl TimeReport {
+    pub(crate) fn new<'a>(
+        tasks: impl Iterator<Item = &'a Task>,
+        from: Date,
+        to: Date,
+        now: OffsetDateTime,
+    ) -> TimeReport {
+        // days are counted in the time zone of `now`
+        let start = from.midnight().assume_offset(now.offset());
+        let end = to
+            .saturating_add(Duration::DAY)
+            .midnight()
+            .assume_offset(now.offset());
+
+        let mut per_task = Vec::new();
+        let mut per_tag: BTreeMap<String, Duration> = BTreeMap::new();
+        for task in tasks {
+            let spent = task.tracked_between(start, end, now);
+            if !spent.is_positive() {
+                continue;
+            }
+            for tag in &task.tags {
+                *per_tag.entry(tag.clone()).or_default() += spent;
+            }
+            per_task.push((task.title.clone(), spent));
+        }
+        per_task.sort_by_key(|(_, spent)| std::cmp::Reverse(*spent));
+
+        TimeReport {
+            from,
+            to,
+            total: per_task.iter().map(|(_, spent)| *spent).sum(),
+            tasks: per_task,
+            tags: per_tag.into_iter().collect(),
+        }
+    }
+}
@@ -1,104 +1,900 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Stylize},
+    layout::{Constraint, Direction, Flex, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
     widgets::{
         calendar::{CalendarEventStore, Monthly},
-        Block, Borders, Clear, Padding, Paragraph,
+        Bar, BarChart, BarGroup, Block, Borders, Clear, Gauge, List, ListItem, Padding, Paragraph,
+        Row, Sparkline, Table, Wrap,
     },
     Frame,
 };
-use time::OffsetDateTime;
+use strum::IntoEnumIterator;
+use time::{Date, Duration, OffsetDateTime};
 
 use crate::{
-    app::{App, EditMode, Popup, TaskCreationMode},
-    keys_hint::KeysHint,
+    agenda::agenda_rows,
+    app::{App, CurrentScreen, EditMode, Popup, TaskCreationMode},
+    board::column_rows,
+    command_palette::CommandPalette,
+    dates::{duration_text, relative_due, span_text, today},
+    keys_hint::{HintBar, KeysHint},
+    panes::Pane,
+    planner::day_rows,
+    pomodoro::{FocusTimer, Period},
+    stats::{self, Stats},
+    task::{Task, TaskStatus, TaskView},
+    theme::Theme,
+    tracking::TimeReport,
 };
 
-pub fn ui(frame: &mut Frame, app: &App) {
-    let app_chunks = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(frame.area());
-    let main_screen_chunk = app_chunks[0];
-    let edit_screen_chunk = app_chunks[1];
-    if let Some(active_task) = &app.current_task {
-        frame.render_widget(active_task.clone(), main_screen_chunk);
+/// Where the clickable parts of the screen were drawn in the last frame.
+#[derive(Default)]
+pub struct ClickAreas {
+    /// The shown task, the mouse wheel scrolls its description.
+    pub task_view: Rect,
+    /// The rows of the task list, inside its border.
+    pub task_list: Rect,
+    pub title_field: Rect,
+    pub description_field: Rect,
+    /// The rows of days and the month they show.
+    pub calendar: Option<(Rect, Date)>,
+    /// The task rows of every day column of the week planner.
+    pub planner_days: Vec<(Rect, Date)>,
+}
+
+impl ClickAreas {
+    /// The planner day drawn at `position` and the row within its column.
+    pub(crate) fn planner_day_at(&self, position: Position) -> Option<(Date, u16)> {
+        self.planner_days
+            .iter()
+            .find(|(area, _)| area.contains(position))
+            .map(|(area, day)| (*day, position.y - area.y))
     }
-    match app.current_screen {
-        crate::app::CurrentScreen::Main => {}
-        crate::app::CurrentScreen::Editing => {
-            if let Some(editing) = &app.edit_mode {
-                let title_text = match &app.task_creation_mode {
-                    TaskCreationMode::Active => "edit current task",
-                    TaskCreationMode::CreateNew => "enter a new task",
-                };
-                let edit_block = Block::default()
-                    .title(title_text)
-                    .borders(Borders::NONE)
-                    .style(Style::default().bg(Color::DarkGray));
-
-                frame.render_widget(edit_block, edit_screen_chunk);
-
-                let edit_chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .margin(1)
-                    .constraints([
-                        Constraint::Percentage(40),
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(30),
-                    ])
-                    .split(edit_screen_chunk);
-                let mut title_block = Block::default().title("Title").borders(Borders::ALL);
-                let mut description_block =
-                    Block::default().title("Description").borders(Borders::ALL);
-
-                let active_style = Style::default().bg(Color::LightYellow).fg(Color::Black);
-
-                match editing {
-                    EditMode::Title => title_block = title_block.style(active_style),
-                    EditMode::Description => {
-                        description_block = description_block.style(active_style)
-                    }
-                    EditMode::Main => {}
-                };
 
-                let task_text = Paragraph::new(app.title_input.clone()).block(title_block);
-                frame.render_widget(task_text, edit_chunks[0]);
+    /// The day of the calendar drawn at `position`.
+    pub(crate) fn calendar_date_at(&self, position: Position) -> Option<Date> {
+        let (area, month) = self.calendar?;
+        if !area.contains(position) {
+            return None;
+        }
+        // every day takes a gutter column and two digits, starting on Sunday
+        let column = (position.x - area.x) / 3;
+        let week = position.y - area.y;
+        if column >= 7 {
+            return None;
+        }
+        let first_of_month = month.replace_day(1).ok()?;
+        let first_shown = first_of_month
+            - Duration::days(first_of_month.weekday().number_days_from_sunday().into());
+        let date = first_shown + Duration::days((week * 7 + column).into());
+        // days of the surrounding months are not drawn
+        (date.month() == month.month()).then_some(date)
+    }
+}
 
-                let description_text =
-                    Paragraph::new(app.description_input.clone()).block(description_block);
-                frame.render_widget(description_text, edit_chunks[1]);
+/// Rows `Monthly` needs for the headers and the most weeks a month can span.
+const CALENDAR_HEIGHT: u16 = 8;
 
-                let mut calendar_event_store = CalendarEventStore::today(Style::new().red().bold());
-                if let Some(active_task) = &app.current_task {
-                    calendar_event_store
-                        .add(active_task.due_time.date(), Style::new().blue().bold());
-                }
+/// The smallest terminal the screens are usable in.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+/// Below this width the panes are stacked.
+const NARROW_WIDTH: u16 = 100;
 
-                let todays_date = OffsetDateTime::now_utc().date();
-                let calendar = Monthly::new(todays_date, calendar_event_store)
-                    .block(Block::new().padding(Padding::new(0, 0, 2, 0)))
-                    .show_month_header(Style::new().bold())
-                    .show_weekdays_header(Style::new().italic());
-                frame.render_widget(calendar, edit_chunks[2]);
-            }
+pub fn ui(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        app.click_areas = ClickAreas::default();
+        render_too_small(frame, area);
+        return;
+    }
+
+    let (overdue, due_today) = app.due_counts();
+    let banner_height = if overdue + due_today > 0 { 1 } else { 0 };
+    let timer_height = if app.focus_timer.is_some() { 1 } else { 0 };
+    let [banner_area, screen_area, timer_area, hint_bar_area] = Layout::vertical([
+        Constraint::Length(banner_height),
+        Constraint::Fill(1),
+        Constraint::Length(timer_height),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+    app.click_areas = ClickAreas::default();
+    render_banner(frame, app, banner_area, overdue, due_today);
+    match app.current_screen {
+        CurrentScreen::Calendar => render_calendar_screen(frame, app, screen_area),
+        CurrentScreen::Agenda => {
+            let border = app.theme.border;
+            render_agenda(frame, app, screen_area, border, true);
         }
+        CurrentScreen::Planner => render_planner(frame, app, screen_area),
+        CurrentScreen::Board => render_board(frame, app, screen_area),
+        CurrentScreen::Dashboard => render_dashboard(frame, app, screen_area),
+        CurrentScreen::Main | CurrentScreen::Editing => render_panes(frame, app, screen_area),
+    }
+
+    if let Some(timer) = &app.focus_timer {
+        render_focus_timer(frame, app, timer, timer_area);
+    }
+
+    let hint_bar = HintBar {
+        context: app.key_context(),
+        key_bindings: &app.key_bindings,
     };
+    frame.render_widget(hint_bar, hint_bar_area);
 
-    #[allow(clippy::single_match)]
     match app.popup {
         Some(Popup::Help) => {
             let help = KeysHint {
-                _screen: app.current_screen,
+                context: app.key_context(),
+                theme: &app.theme,
+                key_bindings: &app.key_bindings,
             };
 
             let center = centered_rect(50, 50, frame.area());
             frame.render_widget(Clear, center);
-            frame.render_widget(help, center);
+            frame.render_stateful_widget(help, center, &mut app.keys_hint);
+        }
+        Some(Popup::Error) => {
+            let message = app
+                .error_message
+                .as_deref()
+                .unwrap_or("Something went wrong");
+            let error = Paragraph::new(message)
+                .wrap(Wrap { trim: true })
+                .style(app.theme.error)
+                .block(Block::bordered().title("Error"));
+
+            let center = centered_rect(50, 20, frame.area());
+            frame.render_widget(Clear, center);
+            frame.render_widget(error, center);
+        }
+        Some(Popup::CommandLine) => {
+            let [prompt_area, input_area] =
+                Layout::horizontal([Constraint::Length(1), Constraint::Fill(1)])
+                    .areas(hint_bar_area);
+            frame.render_widget(Clear, hint_bar_area);
+            frame.render_widget(":", prompt_area);
+            frame.render_widget(&app.command_line.input, input_area);
+            frame.set_cursor_position(app.command_line.input.cursor_position(input_area));
+
+            // like vim's wildmenu, the completions go on the line above
+            if let Some((candidates, selected)) = app.command_line.candidates() {
+                let spans: Vec<Span> = candidates
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, candidate)| {
+                        let span = Span::raw(candidate.as_str());
+                        let span = if i == selected {
+                            span.style(app.theme.selected)
+                        } else {
+                            span
+                        };
+                        [span, Span::raw("  ")]
+                    })
+                    .collect();
+                let menu_area = Rect {
+                    y: screen_area.bottom().saturating_sub(1),
+                    height: 1.min(screen_area.height),
+                    ..screen_area
+                };
+                frame.render_widget(Clear, menu_area);
+                frame.render_widget(
+                    Line::from(spans).style(app.theme.edit_background),
+                    menu_area,
+                );
+            }
+        }
+        Some(Popup::CommandPalette) => {
+            let palette = CommandPalette {
+                context: app.key_context(),
+                theme: &app.theme,
+                key_bindings: &app.key_bindings,
+            };
+
+            let center = centered_rect(60, 50, frame.area());
+            frame.render_widget(Clear, center);
+            frame.render_stateful_widget(palette, center, &mut app.command_palette);
+        }
+        Some(Popup::Reminder) => {
+            let lines: Vec<Line> = app
+                .notices
+                .iter()
+                .map(|notice| {
+                    Line::from(vec![
+                        Span::raw(notice.title.as_str()),
+                        Span::raw(format!("  {}", notice.body)).dim(),
+                    ])
+                })
+                .collect();
+            let reminders = Paragraph::new(lines).block(
+                Block::bordered()
+                    .title("Reminder")
+                    .title_bottom(Line::from("Esc to dismiss").right_aligned())
+                    .border_style(app.theme.due_today),
+            );
+
+            let center = centered_rect(50, 30, frame.area());
+            frame.render_widget(Clear, center);
+            frame.render_widget(reminders, center);
+        }
+        Some(Popup::TimeReport) => {
+            if let Some(report) = &app.time_report {
+                let center = centered_rect(70, 60, frame.area());
+                frame.render_widget(Clear, center);
+                render_time_report(frame, &app.theme, report, center);
+            }
+        }
+        None => {}
+    }
+
+    app.theme.strip_colors(frame.buffer_mut());
+}
+
+/// How far the focus timer is into its period.
+fn render_focus_timer(frame: &mut Frame, app: &App, timer: &FocusTimer, area: Rect) {
+    let remaining = timer.remaining().as_secs();
+    let label = format!(
+        "{} {:02}:{:02} left",
+        timer.period.to_text(),
+        remaining / 60,
+        remaining % 60
+    );
+    let style = match timer.period {
+        Period::Work => app.theme.status_in_progress,
+        Period::Break => app.theme.status_finished,
+    };
+    let gauge = Gauge::default()
+        .ratio(timer.ratio())
+        .label(label)
+        .gauge_style(style);
+    frame.render_widget(gauge, area);
+}
+
+/// A reminder of the tasks needing attention, only drawn when there are any.
+fn render_banner(frame: &mut Frame, app: &App, area: Rect, overdue: usize, due_today: usize) {
+    let mut spans = Vec::new();
+    if overdue > 0 {
+        spans.push(Span::styled(
+            format!(" {} overdue ", overdue),
+            app.theme.overdue,
+        ));
+    }
+    if due_today > 0 {
+        spans.push(Span::styled(
+            format!(" {} due today ", due_today),
+            app.theme.due_today,
+        ));
+    }
+    frame.render_widget(Line::from(spans).centered(), area);
+}
+
+/// Splits `area` between the configured panes, the editor takes the place of
+/// all but the detail pane while editing.
+fn render_panes(frame: &mut Frame, app: &mut App, area: Rect) {
+    let direction = if area.width < NARROW_WIDTH {
+        Direction::Vertical
+    } else {
+        Direction::Horizontal
+    };
+    // `None` stands for the editor
+    let slots: Vec<(Option<Pane>, u16)> = match app.current_screen {
+        CurrentScreen::Editing => {
+            let mut slots = Vec::new();
+            let mut editor_size: u32 = 0;
+            for (pane, size) in app.panes.panes() {
+                if *pane == Pane::Detail {
+                    slots.push((Some(Pane::Detail), *size));
+                } else {
+                    editor_size += u32::from(*size);
+                }
+            }
+            let editor_size = match editor_size.min(u16::MAX.into()) as u16 {
+                // only the detail pane is shown, the editor gets as much room
+                0 => slots.first().map_or(1, |(_, size)| *size),
+                size => size,
+            };
+            slots.push((None, editor_size));
+            slots
+        }
+        _ => app
+            .panes
+            .panes()
+            .iter()
+            .map(|(pane, size)| (Some(*pane), *size))
+            .collect(),
+    };
+
+    let constraints = slots.iter().map(|(_, size)| Constraint::Fill(*size));
+    let areas = Layout::new(direction, constraints).split(area);
+    let show_focus = slots.len() > 1 && matches!(app.current_screen, CurrentScreen::Main);
+    for ((slot, _), pane_area) in slots.iter().zip(areas.iter()) {
+        let focused = show_focus && *slot == Some(app.panes.focused());
+        let border = if focused {
+            app.theme.focused_border
+        } else {
+            app.theme.border
+        };
+        match slot {
+            Some(Pane::Detail) => render_detail(frame, app, *pane_area, focused),
+            Some(Pane::List) => render_task_list(frame, app, *pane_area, border),
+            Some(Pane::Calendar) => {
+                let block = Block::bordered().title("Calendar").border_style(border);
+                let calendar_area = block.inner(*pane_area);
+                frame.render_widget(block, *pane_area);
+                let due_dates = due_date_styles(app);
+                render_calendar(frame, app, calendar_area, today(), &due_dates);
+            }
+            Some(Pane::Agenda) => render_agenda(frame, app, *pane_area, border, false),
+            Some(Pane::Stats) => render_stats(frame, app, *pane_area, border),
+            None => render_editor(frame, app, *pane_area),
+        }
+    }
+}
+
+fn render_detail(frame: &mut Frame, app: &mut App, area: Rect, focused: bool) {
+    app.click_areas.task_view = area;
+    if let Some(active_task) = &app.current_task {
+        let task_view = TaskView {
+            task: active_task,
+            theme: &app.theme,
+            dates: &app.config.dates,
+            focused,
+        };
+        frame.render_stateful_widget(task_view, area, &mut app.task_view);
+    }
+}
+
+/// The title and description fields with a calendar of the due date.
+fn render_editor(frame: &mut Frame, app: &mut App, area: Rect) {
+    let Some(editing) = app.edit_mode else {
+        return;
+    };
+    let title_text = match &app.task_creation_mode {
+        TaskCreationMode::Active => "edit current task",
+        TaskCreationMode::CreateNew => "enter a new task",
+    };
+    let edit_block = Block::default()
+        .title(title_text)
+        .borders(Borders::NONE)
+        .style(app.theme.edit_background);
+
+    frame.render_widget(edit_block, area);
+
+    let edit_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ])
+        .split(area);
+    let mut title_block = Block::default()
+        .title("Title")
+        .borders(Borders::ALL)
+        .border_style(app.theme.border);
+    let mut description_block = Block::default()
+        .title("Description")
+        .borders(Borders::ALL)
+        .border_style(app.theme.border);
+    let vim_mode_text = format!("-- {} --", app.vim.mode.to_text());
+
+    let active_style = app.theme.active_field;
+
+    match editing {
+        EditMode::Title => title_block = title_block.style(active_style),
+        EditMode::Description => description_block = description_block.style(active_style),
+        EditMode::Main => {}
+    };
+    if app.config.editor.vim_mode {
+        match editing {
+            EditMode::Title => title_block = title_block.title_bottom(vim_mode_text.as_str()),
+            EditMode::Description => {
+                description_block = description_block.title_bottom(vim_mode_text.as_str())
+            }
+            EditMode::Main => {}
+        }
+    }
+
+    app.click_areas.title_field = edit_chunks[0];
+    app.click_areas.description_field = edit_chunks[1];
+    let title_area = title_block.inner(edit_chunks[0]);
+    frame.render_widget(title_block, edit_chunks[0]);
+    frame.render_widget(&app.title_input, title_area);
+
+    let description_area = description_block.inner(edit_chunks[1]);
+    frame.render_widget(description_block, edit_chunks[1]);
+    frame.render_widget(&app.description_input, description_area);
+
+    match editing {
+        EditMode::Title => frame.set_cursor_position(app.title_input.cursor_position(title_area)),
+        EditMode::Description => {
+            frame.set_cursor_position(app.description_input.cursor_position(description_area))
+        }
+        EditMode::Main => {}
+    }
+
+    let due_dates: Vec<(Date, Style)> = match app.task_creation_mode {
+        TaskCreationMode::Active => app
+            .current_task
+            .iter()
+            .map(|task| (task.due_time.date(), calendar_style(&app.theme, task)))
+            .collect(),
+        TaskCreationMode::CreateNew => app
+            .new_task_due
+            .iter()
+            .map(|date| (*date, app.theme.calendar_due))
+            .collect(),
+    };
+    render_calendar(frame, app, edit_chunks[2], today(), &due_dates);
+}
+
+/// How a due date is marked in the calendar, by status with overdue tasks standing out.
+fn calendar_style(theme: &Theme, task: &Task) -> Style {
+    match task.task_status {
+        _ if task.is_overdue() => theme.overdue,
+        TaskStatus::InProgress => theme.calendar_due,
+        TaskStatus::Finished => theme.status_finished,
+    }
+}
+
+/// The due dates of every task, when tasks share a day the most pressing
+/// one comes last so its style wins.
+fn due_date_styles(app: &App) -> Vec<(Date, Style)> {
+    let mut tasks: Vec<&Task> = app.all_tasks().collect();
+    tasks.sort_by_key(|task| {
+        (
+            task.is_overdue(),
+            matches!(task.task_status, TaskStatus::InProgress),
+        )
+    });
+    tasks
+        .iter()
+        .map(|task| (task.due_time.date(), calendar_style(&app.theme, task)))
+        .collect()
+}
+
+/// The month of `shown` with today and `due_dates` marked, clicking a day
+/// sets the due date in the editor and opens it on the calendar screen elsewhere.
+fn render_calendar(
+    frame: &mut Frame,
+    app: &mut App,
+    area: Rect,
+    shown: Date,
+    due_dates: &[(Date, Style)],
+) {
+    let mut calendar_event_store = CalendarEventStore::today(app.theme.calendar_today);
+    for (date, style) in due_dates {
+        calendar_event_store.add(*date, *style);
+    }
+
+    // Monthly draws past its area when it is too short, so only pad
+    // the calendar when there is room and skip it when there is none
+    let padding = area.height.saturating_sub(CALENDAR_HEIGHT).min(2);
+    if area.height >= CALENDAR_HEIGHT {
+        let calendar = Monthly::new(shown, calendar_event_store)
+            .block(Block::new().padding(Padding::new(0, 0, padding, 0)))
+            .show_month_header(Style::new().bold())
+            .show_weekdays_header(Style::new().italic());
+        frame.render_widget(calendar, area);
+
+        // the month and weekday headers come before the days
+        let days_area = Rect {
+            y: area.y + padding + 2,
+            height: CALENDAR_HEIGHT - 2,
+            ..area
+        };
+        app.click_areas.calendar = Some((days_area, shown));
+    }
+}
+
+/// The month of the selected day next to the tasks due that day.
+fn render_calendar_screen(frame: &mut Frame, app: &mut App, area: Rect) {
+    let selected = app.calendar.selected();
+    let block = Block::bordered()
+        .title("Calendar")
+        .border_style(app.theme.border);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // the month is 7 days of 3 columns, the list goes beside it when there is room
+    let (calendar_area, day_area) = if inner.width >= 60 {
+        let [calendar_area, day_area] =
+            Layout::horizontal([Constraint::Length(23), Constraint::Fill(1)]).areas(inner);
+        (calendar_area, day_area)
+    } else {
+        let [calendar_area, day_area] =
+            Layout::vertical([Constraint::Length(CALENDAR_HEIGHT + 2), Constraint::Fill(1)])
+                .areas(inner);
+        (calendar_area, day_area)
+    };
+
+    let mut due_dates = due_date_styles(app);
+    let selected_style = due_dates
+        .iter()
+        .rev()
+        .find(|(date, _)| *date == selected)
+        .map_or(Style::new(), |(_, style)| *style)
+        .patch(app.theme.selected);
+    due_dates.push((selected, selected_style));
+    let [calendar_area] = Layout::horizontal([Constraint::Length(21)])
+        .flex(Flex::Center)
+        .areas(calendar_area);
+    render_calendar(frame, app, calendar_area, selected, &due_dates);
+
+    let dates = &app.config.dates;
+    let mut tasks: Vec<&Task> = app
+        .all_tasks()
+        .filter(|task| task.due_time.date() == selected)
+        .collect();
+    tasks.sort_by_key(|task| task.due_time);
+    let items: Vec<ListItem> = tasks
+        .iter()
+        .map(|task| {
+            let marker = task.task_status.marker();
+            let line = Line::from(vec![
+                Span::raw(format!("{} ", marker)),
+                Span::raw(format!("{} ", dates.format_clock(task.due_time))).dim(),
+                Span::raw(task.title.as_str()),
+            ]);
+            ListItem::new(line).style(app.theme.due_style(task))
+        })
+        .collect();
+    let day_block = Block::bordered()
+        .title(format!("Due {}", dates.format_date(selected)))
+        .border_style(app.theme.border);
+    if items.is_empty() {
+        let nothing = Paragraph::new("Nothing due").dim().block(day_block);
+        frame.render_widget(nothing, day_area);
+    } else {
+        frame.render_widget(List::new(items).block(day_block), day_area);
+    }
+}
+
+/// Unfinished tasks under their agenda headings, the earliest first.
+///
+/// On the agenda screen the selected task is highlighted and kept in view.
+fn render_agenda(frame: &mut Frame, app: &mut App, area: Rect, border: Style, screen: bool) {
+    let rows = agenda_rows(app.all_tasks());
+    app.agenda.selected = app.agenda.selected.min(rows.len().saturating_sub(1));
+    let tasks: Vec<&Task> = app.all_tasks().collect();
+    let dates = &app.config.dates;
+    let now = OffsetDateTime::now_utc();
+    let mut items = Vec::new();
+    let mut selected_line = None;
+    let mut heading = None;
+    for (row, (group, index)) in rows.iter().enumerate() {
+        if heading != Some(*group) {
+            items.push(ListItem::new(Line::from(group.title()).bold()));
+            heading = Some(*group);
+        }
+        if screen && row == app.agenda.selected {
+            selected_line = Some(items.len());
+        }
+        let task = tasks[*index];
+        let mut spans = vec![
+            Span::raw(format!("  {} ", dates.format_date(task.due_time.date()))).dim(),
+            Span::raw(task.title.clone()),
+        ];
+        if dates.relative {
+            let due = relative_due(task.due_time, now, task.is_overdue());
+            spans.push(Span::raw(format!("  {}", due)).dim());
+        }
+        items.push(ListItem::new(Line::from(spans)).style(app.theme.due_style(task)));
+    }
+
+    let block = Block::bordered().title("Agenda").border_style(border);
+    if items.is_empty() {
+        frame.render_widget(Paragraph::new("Nothing to do").dim().block(block), area);
+        return;
+    }
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(app.theme.selected);
+    if screen {
+        app.agenda.list.select(selected_line);
+        frame.render_stateful_widget(list, area, &mut app.agenda.list);
+    } else {
+        frame.render_widget(list, area);
+    }
+}
+
+/// A column for every day of the selected week with the tasks due that day.
+fn render_planner(frame: &mut Frame, app: &mut App, area: Rect) {
+    let week_start = app.planner.week_start();
+    let selected_day = app.planner.day();
+    let today = today();
+    let columns = Layout::horizontal([Constraint::Ratio(1, 7); 7]).split(area);
+    for (i, column) in columns.iter().enumerate() {
+        let day = week_start + Duration::days(i as i64);
+        let rows = day_rows(app.all_tasks(), day);
+        let selected = day == selected_day;
+        if selected {
+            app.planner.row = app.planner.row.min(rows.len().saturating_sub(1));
         }
 
-        _ => {}
+        let tasks: Vec<&Task> = app.all_tasks().collect();
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|index| {
+                let task = tasks[*index];
+                let marker = task.task_status.marker();
+                ListItem::new(format!("{} {}", marker, task.title)).style(app.theme.due_style(task))
+            })
+            .collect();
+
+        let title = format!("{} {}", &day.weekday().to_string()[..3], day.day());
+        let title_style = if day == today {
+            app.theme.calendar_today
+        } else {
+            Style::new()
+        };
+        let block = Block::bordered()
+            .title(Line::styled(title, title_style))
+            .border_style(if selected {
+                app.theme.focused_border
+            } else {
+                app.theme.border
+            });
+        app.click_areas
+            .planner_days
+            .push((block.inner(*column), day));
+        let list = List::new(items).block(block);
+        if selected {
+            let list = list.highlight_style(app.theme.selected);
+            app.planner.list.select(Some(app.planner.row));
+            frame.render_stateful_widget(list, *column, &mut app.planner.list);
+        } else {
+            frame.render_widget(list, *column);
+        }
     }
 }
 
+/// A column of cards for every status, each card with the due date and tags.
+fn render_board(frame: &mut Frame, app: &mut App, area: Rect) {
+    let statuses: Vec<TaskStatus> = TaskStatus::iter().collect();
+    let columns = Layout::horizontal(vec![Constraint::Fill(1); statuses.len()]).split(area);
+    for (i, (status, column)) in statuses.iter().zip(columns.iter()).enumerate() {
+        let rows = column_rows(app.all_tasks(), *status);
+        let selected = i == app.board.column;
+        if selected {
+            app.board.row = app.board.row.min(rows.len().saturating_sub(1));
+        }
+
+        let tasks: Vec<&Task> = app.all_tasks().collect();
+        let dates = &app.config.dates;
+        let now = OffsetDateTime::now_utc();
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|index| {
+                let task = tasks[*index];
+                let mut due = dates.format_date_time(task.due_time);
+                if dates.relative {
+                    due = format!(
+                        "{}, {}",
+                        due,
+                        relative_due(task.due_time, now, task.is_overdue())
+                    );
+                }
+                let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+                let mut lines = vec![
+                    Line::from(task.title.clone()).bold(),
+                    Line::styled(due, app.theme.due_style(task)),
+                ];
+                if !tags.is_empty() {
+                    lines.push(Line::from(tags.join(" ")).dim());
+                }
+                // a blank line between cards
+                lines.push(Line::default());
+                ListItem::new(lines)
+            })
+            .collect();
+
+        let style = match status {
+            TaskStatus::InProgress => app.theme.status_in_progress,
+            TaskStatus::Finished => app.theme.status_finished,
+        };
+        let title = format!("{} ({})", status.to_text(), items.len());
+        let block = Block::bordered()
+            .title(Line::styled(title, style))
+            .border_style(if selected {
+                app.theme.focused_border
+            } else {
+                app.theme.border
+            });
+        let list = List::new(items).block(block);
+        if selected {
+            let list = list.highlight_style(app.theme.selected);
+            app.board.list.select(Some(app.board.row));
+            frame.render_stateful_widget(list, *column, &mut app.board.list);
+        } else {
+            frame.render_widget(list, *column);
+        }
+    }
+}
+
+/// Time spent per task next to the time per tag.
+fn render_time_report(frame: &mut Frame, theme: &Theme, report: &TimeReport, area: Rect) {
+    let block = Block::bordered()
+        .title(format!("Time tracked {} to {}", report.from, report.to))
+        .title_bottom(Line::from(format!("total {}", duration_text(report.total))).right_aligned())
+        .border_style(theme.border);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if report.tasks.is_empty() {
+        frame.render_widget(Paragraph::new("Nothing tracked in this range"), inner);
+        return;
+    }
+
+    let [tasks_area, tags_area] =
+        Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)]).areas(inner);
+    let table = |title: &'static str, rows: &[(String, Duration)]| {
+        let rows = rows
+            .iter()
+            .map(|(name, spent)| Row::new([name.clone(), duration_text(*spent)]));
+        Table::new(rows, [Constraint::Fill(1), Constraint::Length(8)])
+            .header(Row::new([title, "Time"]).style(theme.selected))
+    };
+    frame.render_widget(table("Task", &report.tasks), tasks_area);
+    frame.render_widget(table("Tag", &report.tags), tags_area);
+}
+
+/// Completions over the last days and weeks with a summary above them.
+fn render_dashboard(frame: &mut Frame, app: &mut App, area: Rect) {
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let stats = Stats::new(app.all_tasks(), now);
+    let theme = &app.theme;
+    let [summary_area, per_day_area, trends_area] = Layout::vertical([
+        Constraint::Length(5),
+        Constraint::Fill(1),
+        Constraint::Length(6),
+    ])
+    .areas(area);
+
+    let average = stats.average_completion.map_or("-".to_string(), |average| {
+        span_text(average).unwrap_or_else(|| "under a minute".to_string())
+    });
+    let overdue_rate = stats
+        .overdue_rate
+        .map_or("-".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+    let summary = vec![
+        Line::from(vec![
+            Span::styled(format!("Open {}", stats.open), theme.status_in_progress),
+            Span::raw("   "),
+            Span::styled(
+                format!("Finished {}", stats.finished),
+                theme.status_finished,
+            ),
+            Span::raw("   "),
+            Span::styled(format!("Overdue {}", stats.overdue), theme.overdue),
+        ]),
+        Line::from(format!("Average time to completion  {}", average)),
+        Line::from(format!("Not finished by the due date  {}", overdue_rate)),
+    ];
+    let summary_block = Block::bordered()
+        .title("Statistics")
+        .border_style(theme.border);
+    frame.render_widget(Paragraph::new(summary).block(summary_block), summary_area);
+
+    let today = now.date();
+    let bars: Vec<Bar> = stats
+        .completed_per_day
+        .iter()
+        .map(|(day, count)| {
+            let label = Line::from(day.day().to_string());
+            let label = if *day == today {
+                label.style(theme.calendar_today)
+            } else {
+                label
+            };
+            Bar::default().value(*count).label(label)
+        })
+        .collect();
+    let per_day = BarChart::default()
+        .block(
+            Block::bordered()
+                .title(format!("Finished per day, last {} days", stats::DAYS))
+                .border_style(theme.border),
+        )
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(3)
+        .bar_gap(1)
+        .bar_style(theme.status_finished);
+    frame.render_widget(per_day, per_day_area);
+
+    let [per_week_area, open_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(trends_area);
+    let per_week = Sparkline::default()
+        .block(
+            Block::bordered()
+                .title(format!("Finished per week, last {}", stats::WEEKS))
+                .border_style(theme.border),
+        )
+        .data(&stats.completed_per_week)
+        .style(theme.status_finished);
+    frame.render_widget(per_week, per_week_area);
+    let open = Sparkline::default()
+        .block(
+            Block::bordered()
+                .title(format!("Open tasks, last {} days", stats::DAYS))
+                .border_style(theme.border),
+        )
+        .data(&stats.open_per_day)
+        .style(theme.status_in_progress);
+    frame.render_widget(open, open_area);
+}
+
+/// Counts of the tasks by status.
+fn render_stats(frame: &mut Frame, app: &mut App, area: Rect, border: Style) {
+    let (mut open, mut finished) = (0, 0);
+    for task in app.all_tasks() {
+        match task.task_status {
+            TaskStatus::InProgress => open += 1,
+            TaskStatus::Finished => finished += 1,
+        }
+    }
+    let (overdue, due_today) = app.due_counts();
+    let lines = vec![
+        Line::from(format!("Open      {}", open)).style(app.theme.status_in_progress),
+        Line::from(format!("Finished  {}", finished)).style(app.theme.status_finished),
+        Line::from(format!("Overdue   {}", overdue)).style(app.theme.overdue),
+        Line::from(format!("Due today {}", due_today)).style(app.theme.due_today),
+    ];
+    let block = Block::bordered().title("Statistics").border_style(border);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_too_small(frame: &mut Frame, area: Rect) {
+    let message = format!(
+        "Terminal too small\n\n{}x{}, needs at least {}x{}",
+        area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+    );
+    let [middle] = Layout::vertical([Constraint::Length(3)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Paragraph::new(message).centered(), middle);
+}
+
+/// Every task, the shown one first and highlighted.
+fn render_task_list(frame: &mut Frame, app: &mut App, area: Rect, border: Style) {
+    let now = OffsetDateTime::now_utc();
+    let tasks: Vec<&Task> = app.all_tasks().collect();
+    let items: Vec<ListItem> = app
+        .listed_tasks()
+        .into_iter()
+        .map(|i| {
+            let task = tasks[i];
+            let marker = task.task_status.marker();
+            let mut spans = vec![Span::raw(format!("{} {}", marker, task.title))];
+            if app.config.dates.relative && matches!(task.task_status, TaskStatus::InProgress) {
+                let due = relative_due(task.due_time, now, task.is_overdue());
+                spans.push(Span::raw(format!("  {}", due)).dim());
+            }
+            let style = app.theme.due_style(task);
+            let item = ListItem::new(Line::from(spans));
+            if i == 0 && app.current_task.is_some() {
+                // keep the due colour visible under the highlight
+                item.style(style.patch(app.theme.selected))
+            } else {
+                item.style(style)
+            }
+        })
+        .collect();
+
+    let block = Block::bordered()
+        .title(format!("Tasks ({})", items.len()))
+        .border_style(border);
+    let list_area = block.inner(area);
+    let max_offset = items.len().saturating_sub(list_area.height as usize);
+    let offset = app.task_list.offset_mut();
+    *offset = (*offset).min(max_offset);
+    app.click_areas.task_list = list_area;
+
+    frame.render_stateful_widget(List::new(items).block(block), area, &mut app.task_list);
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     // Cut the given rectangle into three vertical pieces
@@ -0,0 +1,552 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::text_area::TextArea;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum VimMode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl VimMode {
+    pub(crate) fn to_text(self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+            VimMode::VisualLine => "VISUAL LINE",
+        }
+    }
+}
+
+/// What the caller should do after a key was handed to [`Vim::input`].
+#[derive(PartialEq, Eq)]
+pub enum VimOutcome {
+    Handled,
+    /// Esc was pressed in normal mode, the field should lose focus.
+    LeaveField,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+impl Operator {
+    fn from_char(ch: char) -> Option<Operator> {
+        match ch {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordForward,
+    WordBack,
+    LineStart,
+    LineEnd,
+    FirstLine,
+    LastLine,
+}
+
+impl Motion {
+    fn from_char(ch: char) -> Option<Motion> {
+        match ch {
+            'h' => Some(Motion::Left),
+            'l' => Some(Motion::Right),
+            'k' => Some(Motion::Up),
+            'j' => Some(Motion::Down),
+            'w' => Some(Motion::WordForward),
+            'b' => Some(Motion::WordBack),
+            '0' => Some(Motion::LineStart),
+            '$' => Some(Motion::LineEnd),
+            'G' => Some(Motion::LastLine),
+            _ => None,
+        }
+    }
+
+    fn is_linewise(self) -> bool {
+        matches!(
+            self,
+            Motion::Up | Motion::Down | Motion::FirstLine | Motion::LastLine
+        )
+    }
+}
+
+#[derive(Clone)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+/// Vim style modal editing for a [`TextArea`].
+///
+/// Registers are shared between fields, so text yanked from the description
+/// can be put into the title.
+#[derive(Default)]
+pub struct Vim {
+    pub(crate) mode: VimMode,
+    operator: Option<Operator>,
+    awaiting_g: bool,
+    awaiting_register: bool,
+    register: Option<char>,
+    registers: HashMap<char, Register>,
+    visual_anchor: (usize, usize),
+}
+
+impl Vim {
+    /// Back to normal mode with nothing pending, used when a field gains focus.
+    pub(crate) fn reset(&mut self) {
+        self.mode = VimMode::Normal;
+        self.clear_pending();
+    }
+
+    pub(crate) fn input(
+        &mut self,
+        key: KeyEvent,
+        text: &mut TextArea,
+        multiline: bool,
+    ) -> VimOutcome {
+        match self.mode {
+            VimMode::Insert => {
+                match key.code {
+                    KeyCode::Esc => {
+                        self.mode = VimMode::Normal;
+                        let (row, col) = text.cursor();
+                        text.set_cursor((row, col.saturating_sub(1)));
+                    }
+                    KeyCode::Enter if multiline => text.insert_newline(),
+                    KeyCode::Enter => {}
+                    _ => {
+                        text.input(key);
+                    }
+                }
+                VimOutcome::Handled
+            }
+            VimMode::Normal => self.normal_input(key, text, multiline),
+            VimMode::Visual | VimMode::VisualLine => {
+                self.visual_input(key, text);
+                VimOutcome::Handled
+            }
+        }
+    }
+
+    fn normal_input(&mut self, key: KeyEvent, text: &mut TextArea, multiline: bool) -> VimOutcome {
+        let ch = match key.code {
+            KeyCode::Char(ch) => ch,
+            KeyCode::Esc => {
+                let pending = self.operator.is_some()
+                    || self.awaiting_g
+                    || self.awaiting_register
+                    || self.register.is_some();
+                self.clear_pending();
+                return if pending {
+                    VimOutcome::Handled
+                } else {
+                    VimOutcome::LeaveField
+                };
+            }
+            code => match arrow_motion(code) {
+                Some(ch) => ch,
+                None => return VimOutcome::Handled,
+            },
+        };
+
+        if self.awaiting_register {
+            self.awaiting_register = false;
+            if ch.is_ascii_alphanumeric() || ch == '"' {
+                self.register = Some(ch);
+            }
+            return VimOutcome::Handled;
+        }
+
+        let motion = if self.awaiting_g {
+            self.awaiting_g = false;
+            if ch == 'g' {
+                Some(Motion::FirstLine)
+            } else {
+                self.clear_pending();
+                return VimOutcome::Handled;
+            }
+        } else if ch == 'g' {
+            self.awaiting_g = true;
+            return VimOutcome::Handled;
+        } else {
+            Motion::from_char(ch)
+        };
+
+        if let Some(operator) = self.operator {
+            self.operator = None;
+            let row = text.cursor().0;
+            if Operator::from_char(ch) == Some(operator) {
+                self.apply_linewise(operator, row, row, text);
+            } else if let Some(motion) = motion {
+                let target = motion_target(motion, text);
+                // like vim, `dj` on the last line fails instead of taking that line
+                if matches!(motion, Motion::Up | Motion::Down) && target.0 == row {
+                    self.register = None;
+                } else if motion.is_linewise() {
+                    self.apply_linewise(operator, row.min(target.0), row.max(target.0), text);
+                } else {
+                    let cursor = text.cursor();
+                    self.apply_charwise(operator, cursor.min(target), cursor.max(target), text);
+                }
+            } else {
+                self.register = None;
+            }
+            return VimOutcome::Handled;
+        }
+
+        if let Some(motion) = motion {
+            move_cursor(motion, text);
+            return VimOutcome::Handled;
+        }
+
+        let (row, col) = text.cursor();
+        match ch {
+            '"' => self.awaiting_register = true,
+            'd' | 'c' | 'y' => self.operator = Operator::from_char(ch),
+            'i' => self.mode = VimMode::Insert,
+            'a' => {
+                text.set_cursor((row, col + 1));
+                self.mode = VimMode::Insert;
+            }
+            'I' => {
+                text.move_line_start();
+                self.mode = VimMode::Insert;
+            }
+            'A' => {
+                text.move_line_end();
+                self.mode = VimMode::Insert;
+            }
+            'o' | 'O' if multiline => {
+                let new_row = if ch == 'o' { row + 1 } else { row };
+                text.insert_lines(new_row, "");
+                self.mode = VimMode::Insert;
+            }
+            'x' if col < text.line_len(row) => {
+                self.apply_charwise(Operator::Delete, (row, col), (row, col + 1), text);
+            }
+            'D' => {
+                let end = (row, text.line_len(row));
+                self.apply_charwise(Operator::Delete, (row, col), end, text);
+            }
+            'C' => {
+                let end = (row, text.line_len(row));
+                self.apply_charwise(Operator::Change, (row, col), end, text);
+            }
+            'p' | 'P' => self.put(ch == 'p', text, multiline),
+            'v' => self.enter_visual(VimMode::Visual, text),
+            'V' => self.enter_visual(VimMode::VisualLine, text),
+            _ => self.clear_pending(),
+        }
+        VimOutcome::Handled
+    }
+
+    fn visual_input(&mut self, key: KeyEvent, text: &mut TextArea) {
+        let ch = match key.code {
+            KeyCode::Char(ch) => ch,
+            KeyCode::Esc => {
+                self.leave_visual(text);
+                return;
+            }
+            code => match arrow_motion(code) {
+                Some(ch) => ch,
+                None => return,
+            },
+        };
+
+        if self.awaiting_register {
+            self.awaiting_register = false;
+            if ch.is_ascii_alphanumeric() || ch == '"' {
+                self.register = Some(ch);
+            }
+            return;
+        }
+
+        let motion = if self.awaiting_g {
+            self.awaiting_g = false;
+            (ch == 'g').then_some(Motion::FirstLine)
+        } else if ch == 'g' {
+            self.awaiting_g = true;
+            return;
+        } else {
+            Motion::from_char(ch)
+        };
+
+        if let Some(motion) = motion {
+            move_cursor(motion, text);
+            self.update_highlight(text);
+            return;
+        }
+
+        let operator = match ch {
+            '"' => {
+                self.awaiting_register = true;
+                return;
+            }
+            'x' => Some(Operator::Delete),
+            _ => Operator::from_char(ch),
+        };
+        if let Some(operator) = operator {
+            let (start, end) = self.selection(text);
+            let linewise = self.mode == VimMode::VisualLine;
+            // leaving clears what is pending, but the register is still needed
+            let register = self.register.take();
+            self.leave_visual(text);
+            self.register = register;
+            if linewise {
+                self.apply_linewise(operator, start.0, end.0, text);
+            } else {
+                self.apply_charwise(operator, start, end, text);
+            }
+            return;
+        }
+        match ch {
+            'v' if self.mode == VimMode::Visual => self.leave_visual(text),
+            'V' if self.mode == VimMode::VisualLine => self.leave_visual(text),
+            'v' => self.mode = VimMode::Visual,
+            'V' => self.mode = VimMode::VisualLine,
+            _ => {}
+        }
+        self.update_highlight(text);
+    }
+
+    fn enter_visual(&mut self, mode: VimMode, text: &mut TextArea) {
+        self.mode = mode;
+        self.visual_anchor = text.cursor();
+        self.update_highlight(text);
+    }
+
+    fn leave_visual(&mut self, text: &mut TextArea) {
+        self.mode = VimMode::Normal;
+        self.clear_pending();
+        text.set_highlight(None);
+    }
+
+    /// The selected range, with the end exclusive.
+    fn selection(&self, text: &TextArea) -> ((usize, usize), (usize, usize)) {
+        let cursor = text.cursor();
+        let start = self.visual_anchor.min(cursor);
+        let end = self.visual_anchor.max(cursor);
+        if self.mode == VimMode::VisualLine {
+            ((start.0, 0), (end.0, text.line_len(end.0)))
+        } else {
+            (start, (end.0, (end.1 + 1).min(text.line_len(end.0))))
+        }
+    }
+
+    fn update_highlight(&self, text: &mut TextArea) {
+        match self.mode {
+            VimMode::Visual | VimMode::VisualLine => {
+                let selection = self.selection(text);
+                text.set_highlight(Some(selection));
+            }
+            _ => text.set_highlight(None),
+        }
+    }
+
+    fn apply_charwise(
+        &mut self,
+        operator: Operator,
+        start: (usize, usize),
+        end: (usize, usize),
+        text: &mut TextArea,
+    ) {
+        match operator {
+            Operator::Yank => {
+                self.store(text.text_between(start, end), false, true);
+                text.set_cursor(start);
+            }
+            Operator::Delete | Operator::Change => {
+                let removed = text.delete_between(start, end);
+                self.store(removed, false, false);
+                if operator == Operator::Change {
+                    self.mode = VimMode::Insert;
+                }
+            }
+        }
+    }
+
+    fn apply_linewise(
+        &mut self,
+        operator: Operator,
+        first: usize,
+        last: usize,
+        text: &mut TextArea,
+    ) {
+        match operator {
+            Operator::Yank => {
+                let end = (last, text.line_len(last));
+                self.store(text.text_between((first, 0), end), true, true);
+                text.set_cursor((first, 0));
+            }
+            Operator::Delete => {
+                let removed = text.delete_lines(first, last);
+                self.store(removed, true, false);
+            }
+            Operator::Change => {
+                // keep an empty line to type into, like `cc` does
+                let end = (last, text.line_len(last));
+                let removed = text.delete_between((first, 0), end);
+                self.store(removed, true, false);
+                self.mode = VimMode::Insert;
+            }
+        }
+    }
+
+    fn put(&mut self, after: bool, text: &mut TextArea, multiline: bool) {
+        let name = self.register.take().unwrap_or('"').to_ascii_lowercase();
+        let Some(register) = self.registers.get(&name).cloned() else {
+            return;
+        };
+        let (row, col) = text.cursor();
+        if register.linewise && multiline {
+            text.insert_lines(if after { row + 1 } else { row }, &register.text);
+        } else {
+            let at = if after && text.line_len(row) > 0 {
+                col + 1
+            } else {
+                col
+            };
+            text.set_cursor((row, at));
+            text.insert_str(&register.text, multiline);
+            text.move_left();
+        }
+    }
+
+    /// Saves text that was deleted or yanked into the selected register.
+    ///
+    /// The unnamed register always gets a copy, yanks also go to `0` and an
+    /// uppercase register name appends to its lowercase register.
+    fn store(&mut self, text: String, linewise: bool, yank: bool) {
+        let register = Register { text, linewise };
+        match self.register.take() {
+            Some(name) if name.is_ascii_uppercase() => {
+                let entry = self
+                    .registers
+                    .entry(name.to_ascii_lowercase())
+                    .or_insert(Register {
+                        text: String::new(),
+                        linewise,
+                    });
+                if entry.linewise && !entry.text.is_empty() {
+                    entry.text.push('\n');
+                }
+                entry.text.push_str(&register.text);
+            }
+            Some(name) if name != '"' => {
+                self.registers.insert(name, register.clone());
+            }
+            _ if yank => {
+                self.registers.insert('0', register.clone());
+            }
+            _ => {}
+        }
+        self.registers.insert('"', register);
+    }
+
+    fn clear_pending(&mut self) {
+        self.operator = None;
+        self.awaiting_g = false;
+        self.awaiting_register = false;
+        self.register = None;
+    }
+}
+
+fn arrow_motion(code: KeyCode) -> Option<char> {
+    match code {
+        KeyCode::Left => Some('h'),
+        KeyCode::Right => Some('l'),
+        KeyCode::Up => Some('k'),
+        KeyCode::Down => Some('j'),
+        KeyCode::Home => Some('0'),
+        KeyCode::End => Some('$'),
+        _ => None,
+    }
+}
+
+fn motion_target(motion: Motion, text: &TextArea) -> (usize, usize) {
+    let (row, col) = text.cursor();
+    let last_row = text.lines().len() - 1;
+    match motion {
+        Motion::Left => (row, col.saturating_sub(1)),
+        Motion::Right => (row, (col + 1).min(text.line_len(row))),
+        Motion::Up => (row.saturating_sub(1), col),
+        Motion::Down => ((row + 1).min(last_row), col),
+        Motion::WordForward => text.next_word_position(),
+        Motion::WordBack => text.previous_word_position(),
+        Motion::LineStart => (row, 0),
+        Motion::LineEnd => (row, text.line_len(row)),
+        Motion::FirstLine => (0, 0),
+        Motion::LastLine => (last_row, 0),
+    }
+}
+
+/// Moves the cursor, keeping it on a character like normal mode does.
+fn move_cursor(motion: Motion, text: &mut TextArea) {
+    let (row, col) = motion_target(motion, text);
+    let last_col = text.line_len(row).saturating_sub(1);
+    text.set_cursor((row, col.min(last_col)));
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn type_keys(vim: &mut Vim, text: &mut TextArea, keys: &str) {
+        for ch in keys.chars() {
+            vim.input(
+                KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE),
+                text,
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn visual_yank_into_named_register() {
+        let (mut vim, mut text) = (Vim::default(), TextArea::default());
+        text.set_text("hello world");
+        type_keys(&mut vim, &mut text, "0vlll\"ay$\"ap");
+        assert_eq!(text.text(), "hello worldhell");
+    }
+
+    #[test]
+    fn visual_line_delete_into_named_register() {
+        let (mut vim, mut text) = (Vim::default(), TextArea::default());
+        text.set_text("one\ntwo");
+        type_keys(&mut vim, &mut text, "ggV\"adx\"ap");
+        // `x` replaced the unnamed register, `a` still has the line
+        assert_eq!(text.text(), "wo\none");
+    }
+
+    #[test]
+    fn line_motions_past_the_text_do_nothing() {
+        let (mut vim, mut text) = (Vim::default(), TextArea::default());
+        text.set_text("one\ntwo");
+        type_keys(&mut vim, &mut text, "dj");
+        assert_eq!(text.text(), "one\ntwo");
+        type_keys(&mut vim, &mut text, "ggdk");
+        assert_eq!(text.text(), "one\ntwo");
+        type_keys(&mut vim, &mut text, "dj");
+        assert_eq!(text.text(), "");
+    }
+}